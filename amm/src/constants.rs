@@ -1,4 +1,24 @@
 // TODO: add to or just implement in top of `protocol.rs`
 
+pub const FEE_SHARE_DENOMINATION: u16 = 10_000; // Fee shares are denominated in basis points, 10_000 = 100%
+pub const MAX_CREATOR_FEE_SHARE: u16 = 5_000; // A market creator can take at most half of the swap fees that would otherwise go to LPs
+pub const MAX_PROTOCOL_FEE_SHARE: u16 = 5_000; // The protocol can take at most half of every swap fee
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000_000_000_000_000; // Precision of the liquidity mining reward per share accumulator
+pub const MAX_CHILD_MARKETS: u64 = 10; // Maximum number of conditional markets that can depend on a single parent market, bounds the work done when the parent finalizes
+pub const SCALAR_DECIMALS: u32 = 24; // Amount of decimals scalar bounds and answers are parsed with, further decimals are truncated
+pub const MAX_ZAP_ROUNDS: u8 = 5; // Maximum number of times a zap re-adds the collateral it freed up by selling leftover outcome tokens
+pub const MAX_ZAP_PRICE_DEVIATION: u128 = 10; // Maximum amount of percentage points a zap may move an outcome's spot price by selling leftover outcome tokens
+pub const MAX_TWAP_OBSERVATIONS: u64 = 96; // Maximum number of observations a pool's price oracle keeps, one per block in which the pool's prices changed
+// Storage tags of the market indexes, single bytes below the ASCII letters the other storage prefixes start with
+pub const MARKETS_BY_CREATOR_TAG: u8 = 0x01;
+pub const CREATOR_MARKETS_TAG: u8 = 0x02; // Followed by the hash of the creator's account id
+pub const MARKETS_BY_CATEGORY_TAG: u8 = 0x03;
+pub const CATEGORY_MARKETS_TAG: u8 = 0x04; // Followed by the hash of the category
+pub const CHILD_MARKETS_TAG: u8 = 0x05;
+pub const PARENT_CHILD_MARKETS_TAG: u8 = 0x06; // Followed by the hash of the parent market id
+pub const UNFINALIZED_MARKETS_TAG: u8 = 0x07;
+pub const FINALIZED_MARKETS_TAG: u8 = 0x08;
+pub const MARKETS_BY_ACCOUNT_TAG: u8 = 0x09;
+pub const ACCOUNT_MARKETS_TAG: u8 = 0x0a; // Followed by the hash of the account id
 pub const MIN_OUTCOMES: u16 = 2; // Minimum number of outcomes a market must have in order to be valid
 pub const MAX_OUTCOMES: u16 = 8; // Minimum number of outcomes a market can have in order to be valid
\ No newline at end of file
@@ -1,4 +1,6 @@
 use crate::*;
+use crate::pricing_model::PricingModelType;
+use crate::market::ScalarPayout;
 
 /**
  * @notice `create_market` args
@@ -17,6 +19,29 @@ pub struct CreateMarketArgs {
     pub collateral_token_id: AccountId, // `AccountId` of collateral that traded in the market
     pub swap_fee: U128, // Swap fee denominated as ration in same denomination as the collateral
     pub is_scalar: bool, // Wether market is scalar market or not
+    pub pricing_model: Option<PricingModelType>, // Market maker curve used by the pool, CPMM if not provided
+    pub fee_schedule: Option<FeeScheduleArgs>, // Optional ramp of the swap fee towards `end_time`
+    pub creator_fee: Option<u16>, // Share of every swap fee, after the protocol fee, that goes to the market creator in basis points
+    pub parent: Option<ParentMarketArgs>, // Optional market outcome this market is conditional on
+    pub scalar_payout: Option<ScalarPayout>, // How a scalar market pays out between its ordered `outcome_tags`, linear if not provided
+}
+
+/**
+ * @notice parent args for `create_market`, a conditional market resolves as invalid unless `outcome` of the parent market wins
+ */
+#[derive(Serialize, Deserialize)]
+pub struct ParentMarketArgs {
+    pub market_id: U64, // id of the parent market
+    pub outcome: u16, // outcome of the parent market the market is conditional on
+}
+
+/**
+ * @notice fee schedule args for `create_market`, the swap fee rises linearly from `swap_fee` to `max_fee` during the last `ramp_duration` before `end_time`
+ */
+#[derive(Serialize, Deserialize)]
+pub struct FeeScheduleArgs {
+    pub max_fee: U128, // Swap fee at `end_time`, in the same denomination as `swap_fee`
+    pub ramp_duration: U64, // Duration in ms before `end_time` during which the fee rises
 }
 
 /**
@@ -28,6 +53,25 @@ pub struct AddLiquidityArgs {
     pub weight_indication: Option<Vec<U128>> // token weights that dictate the initial odd price distribution
 }
 
+/**
+ * @notice `add_liquidity_zap` args
+ */
+#[derive(Serialize, Deserialize)]
+pub struct AddLiquidityZapArgs {
+    pub market_id: U64, // id of the market to add liquidity to
+    pub weight_indication: Option<Vec<U128>> // token weights that dictate the initial odd price distribution, only used if the pool has no liquidity yet
+}
+
+/**
+ * @notice `fund_reward_program` args, the reward token is the token that's transferred
+ */
+#[derive(Serialize, Deserialize)]
+pub struct FundRewardProgramArgs {
+    pub market_id: U64, // id of the market whose LPs receive the rewards
+    pub reward_rate: WrappedBalance, // amount of reward tokens distributed per ms
+    pub duration: U64, // duration of the emission period in ms
+}
+
 /**
  * @notice `buy` args
  */
@@ -38,10 +82,33 @@ pub struct BuyArgs {
     pub min_shares_out: WrappedBalance // the minimum amount of share tokens the user expects out, this is to prevent slippage
 }
 
+/**
+ * @notice `buy_exact_shares` args
+ */
+#[derive(Serialize, Deserialize)]
+pub struct BuyExactSharesArgs {
+    pub market_id: U64, // id of the market that shares are to be purchased from
+    pub outcome_target: u16, // outcome that the sender buys shares in
+    pub shares_out: WrappedBalance, // the exact amount of share tokens the sender wants to buy
+    pub max_collateral_in: WrappedBalance // the maximum amount of collateral the sender is willing to spend, this is to prevent slippage
+}
+
+/**
+ * @notice `mint_complete_set` args
+ */
+#[derive(Serialize, Deserialize)]
+pub struct MintCompleteSetArgs {
+    pub market_id: U64, // id of the market to mint a complete set of outcome tokens in
+}
+
 #[derive(Serialize, Deserialize)]
 pub enum Payload {
     BuyArgs(BuyArgs),
+    BuyExactSharesArgs(BuyExactSharesArgs),
     AddLiquidityArgs(AddLiquidityArgs),
+    AddLiquidityZapArgs(AddLiquidityZapArgs),
+    MintCompleteSetArgs(MintCompleteSetArgs),
+    FundRewardProgramArgs(FundRewardProgramArgs),
     CreateMarketArgs(CreateMarketArgs)
 }
 
@@ -76,7 +143,11 @@ impl FungibleTokenReceiver for AMMContract {
         let payload: Payload = serde_json::from_str(&msg).expect("Failed to parse the payload, invalid `msg` format");
         let res = match payload {
             Payload::BuyArgs(payload) => self.buy(&sender_id, amount, payload), 
+            Payload::BuyExactSharesArgs(payload) => self.buy_exact_shares(&sender_id, amount, payload),
             Payload::AddLiquidityArgs(payload) => self.add_liquidity(&sender_id, amount, payload),
+            Payload::AddLiquidityZapArgs(payload) => self.add_liquidity_zap(&sender_id, amount, payload),
+            Payload::MintCompleteSetArgs(payload) => self.mint_complete_set(&sender_id, amount, payload),
+            Payload::FundRewardProgramArgs(payload) => self.fund_reward_program(&sender_id, amount, payload),
             Payload::CreateMarketArgs(payload) => self.ft_create_market_callback(&sender_id, amount, payload).into()
         };
 
@@ -160,6 +231,7 @@ mod mock_token_basic_tests {
         );
 
         contract.create_market(
+            &alice(),
             &CreateMarketArgs {
                 description: empty_string(),
                 extra_info: empty_string(),
@@ -172,7 +244,12 @@ mod mock_token_basic_tests {
                 collateral_token_id: token(),
                 swap_fee: (10_u128.pow(24) / 50).into(), // 2%
                 challenge_period: U64(1),
-                is_scalar: false
+                is_scalar: false,
+                pricing_model: None,
+                fee_schedule: None,
+                creator_fee: None,
+                parent: None,
+                scalar_payout: None
             }
         );
 
@@ -200,6 +277,7 @@ mod mock_token_basic_tests {
         );
 
         contract.create_market(
+            &alice(),
             &&CreateMarketArgs {
                 description: empty_string(),
                 extra_info: empty_string(),
@@ -212,7 +290,12 @@ mod mock_token_basic_tests {
                 collateral_token_id: token(),
                 swap_fee: (10_u128.pow(24) / 50).into(), // 2%
                 challenge_period: U64(1),
-                is_scalar: false
+                is_scalar: false,
+                pricing_model: None,
+                fee_schedule: None,
+                creator_fee: None,
+                parent: None,
+                scalar_payout: None
             }
         );
 
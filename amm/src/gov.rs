@@ -33,6 +33,80 @@ impl AMMContract {
         self.gov = new_gov.into();
     }
 
+    /**
+     * @returns the `AccountId` protocol fees are withdrawn to
+     */
+    pub fn get_treasury(&self) -> AccountId {
+        self.treasury.to_string()
+    }
+
+    /**
+     * @returns the share of every swap fee that goes to the protocol, denominated in basis points
+     */
+    pub fn get_protocol_fee_share(&self) -> u16 {
+        self.protocol_fee_share
+    }
+
+    /**
+     * @param collateral_token_id is the collateral token to return the protocol fees for
+     * @returns the wrapped amount of protocol fees collected in `collateral_token_id` that haven't been withdrawn yet
+     */
+    pub fn get_protocol_fees(&self, collateral_token_id: ValidAccountId) -> WrappedBalance {
+        U128(self.protocol_fees.get(collateral_token_id.as_ref()).unwrap_or(0))
+    }
+
+    /**
+     * @notice sets the `treasury` account id that protocol fees are withdrawn to
+     * @param `new_treasury` The new treasury
+     */
+    pub fn set_treasury(
+        &mut self,
+        new_treasury: ValidAccountId
+    ) {
+        self.assert_gov();
+        self.treasury = new_treasury.into();
+    }
+
+    /**
+     * @notice sets the share of every swap fee that goes to the protocol instead of the LPs
+     * @param `protocol_fee_share` The new share denominated in basis points, 10_000 being 100%, at most `MAX_PROTOCOL_FEE_SHARE`
+     */
+    pub fn set_protocol_fee_share(
+        &mut self,
+        protocol_fee_share: u16
+    ) {
+        self.assert_gov();
+        assert!(protocol_fee_share <= constants::MAX_PROTOCOL_FEE_SHARE, "ERR_INVALID_PROTOCOL_FEE_SHARE");
+        self.protocol_fee_share = protocol_fee_share;
+    }
+
+    /**
+     * @notice transfers all protocol fees collected in a collateral token to the `treasury`, can only be called by `gov`
+     * @param collateral_token_id is the collateral token to withdraw the protocol fees of
+     * @returns a promise referencing the collateral token transaction
+     */
+    pub fn withdraw_protocol_fees(
+        &mut self,
+        collateral_token_id: ValidAccountId
+    ) -> Promise {
+        self.assert_gov();
+        let collateral_token_id: AccountId = collateral_token_id.into();
+        let amount = self.protocol_fees.get(&collateral_token_id).unwrap_or(0);
+        assert!(amount > 0, "ERR_NO_PROTOCOL_FEES");
+
+        self.protocol_fees.insert(&collateral_token_id, &0);
+        logger::log_protocol_fees_withdrawal(&collateral_token_id, &self.treasury, amount);
+
+        collateral_token::ft_transfer(
+            self.treasury.to_string(),
+            U128(amount),
+            None,
+            &collateral_token_id,
+            1,
+            GAS_BASE_COMPUTE
+        )
+    }
+
     /**
      * @notice pauses the protocol making certain functions un-callable, can only be called by `gov`
      */
@@ -67,6 +141,19 @@ impl AMMContract {
         assert!(!self.paused, "ERR_PROTCOL_PAUSED")
     }
 
+    /**
+     * @notice moves the protocol fees a market's pool collected into the protocol balance of its collateral token
+     * @param market is the market whose pool collected the fees, the caller is responsible for storing it afterwards
+     */
+    pub fn collect_protocol_fees(&mut self, market: &mut Market) {
+        let collected = market.pool.take_protocol_fees();
+        if collected > 0 {
+            let collateral_token_id = &market.pool.collateral_token_id;
+            let protocol_fees = self.protocol_fees.get(collateral_token_id).unwrap_or(0);
+            self.protocol_fees.insert(collateral_token_id, &(protocol_fees + collected));
+        }
+    }
+
     /**
      * @panics if the predecessor is not the oracle
      */
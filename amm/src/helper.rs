@@ -20,6 +20,17 @@ pub fn assert_self() {
     );
 }
 
+/**
+ * @panics if the attached deposit isn't exactly 1 yoctoNEAR, which requires transfers to be signed with a full access key
+ */
+pub fn assert_one_yocto() {
+    assert_eq!(
+        env::attached_deposit(),
+        1,
+        "Requires attached deposit of exactly 1 yoctoNEAR"
+    )
+}
+
 /**
  * @returns a converted timestamp from nanoseconds to miliseconds
  */
@@ -34,6 +45,18 @@ pub fn ms_to_ns(ms_timestamp: u64) -> u64 {
     ms_timestamp * 1_000_000
 }
 
+/**
+ * @notice builds the storage prefix of a collection that's nested in another collection, hashing the key gives every prefix with the same tag the same length so they can't overlap
+ * @param tag is the storage tag of the nested collections
+ * @param key is the key the nested collection is stored under
+ * @returns `tag` followed by the sha256 hash of `key`
+ */
+pub fn nested_storage_prefix(tag: u8, key: &[u8]) -> Vec<u8> {
+    let mut prefix = vec![tag];
+    prefix.extend(env::sha256(key));
+    prefix
+}
+
 pub fn is_promise_success() -> bool {
     assert_eq!(
         env::promise_results_count(),
@@ -47,16 +70,6 @@ pub fn assert_prev_promise_successful() {
     assert_eq!(is_promise_success(), true, "previous promise failed");
 }
 
-pub fn clamp_f64(value: f64, min: f64, max: f64) -> f64 {
-    if value > max {
-        max
-    } else if value < min {
-        min
-    } else {
-        value
-    }
-}
-
 /** 
  * @notice refunds any cleared up or overpaid storage to original sender, also checks if the sender added enough deposit to cover storage
  * @param initial_storage is the storage at the beginning of the function call
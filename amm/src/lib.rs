@@ -1,7 +1,7 @@
 #![allow(clippy::too_many_arguments, clippy::ptr_arg)]
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::json_types::{U128, U64, ValidAccountId};
-use near_sdk::collections::{Vector, UnorderedMap, LookupMap};
+use near_sdk::collections::{Vector, UnorderedMap, UnorderedSet, LookupMap};
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::{
     serde_json,
@@ -34,6 +34,11 @@ mod oracle;
 mod market_creation;
 mod fungible_token;
 mod storage_manager;
+mod token_transfer;
+mod multi_token;
+mod pricing_model;
+mod reward_program;
+mod price_oracle;
 
 pub mod collateral_whitelist; // pub for integration tests 
 pub mod math; // pub for integration tests
@@ -47,6 +52,7 @@ use crate::collateral_whitelist::Whitelist;
 use crate::storage_manager::AccountStorageBalance;
 
 const GAS_BASE_COMPUTE: Gas = 5_000_000_000_000;
+const GAS_FOR_FEES_TRANSFER: Gas = 2 * GAS_BASE_COMPUTE; // LP fee transfer and the callback that re-credits them on failure
 
 #[ext_contract]
 pub trait CollateralToken {
@@ -61,7 +67,15 @@ pub struct AMMContract {
     markets: Vector<Market>, // Vector containing all markets where the index represents the market id
     collateral_whitelist: Whitelist, // Map a token's account id to number of decimals it's denominated in
     paused: bool, // If true certain functions are no longer callable, settable by `gov`
-    accounts: LookupMap<AccountId, AccountStorageBalance> // Storage map
+    accounts: LookupMap<AccountId, AccountStorageBalance>, // Storage map
+    treasury: AccountId, // The account protocol fees are withdrawn to, settable by `gov`
+    protocol_fee_share: u16, // Share of every swap fee that goes to the protocol in basis points, settable by `gov`
+    protocol_fees: LookupMap<AccountId, Balance>, // Maps a collateral token's account id to the protocol fees collected in that token
+    markets_by_creator: LookupMap<AccountId, Vector<u64>>, // Maps a creator's account id to the ids of the markets they created
+    child_markets: LookupMap<u64, Vector<u64>>, // Maps a market id to the ids of the conditional markets that depend on it
+    markets_by_category: LookupMap<String, Vector<u64>>, // Maps a category to the ids of the markets in that category
+    unfinalized_markets: UnorderedSet<u64>, // The ids of the markets that aren't finalized yet
+    finalized_markets: Vector<u64>, // The ids of the finalized markets in order of finalization
 }
 
 #[near_bindgen]
@@ -82,13 +96,23 @@ impl AMMContract {
 
         logger::log_whitelist(&collateral_whitelist);
 
+        let gov: AccountId = gov.into();
+
         Self {
             oracle: oracle.into(),
-            gov: gov.into(),
+            treasury: gov.to_string(),
+            gov,
             markets: Vector::new(b"m".to_vec()),
             collateral_whitelist, 
             paused: false,
             accounts: LookupMap::new(b"as".to_vec()),
+            protocol_fee_share: 0,
+            protocol_fees: LookupMap::new(b"pf".to_vec()),
+            markets_by_creator: LookupMap::new(vec![constants::MARKETS_BY_CREATOR_TAG]),
+            child_markets: LookupMap::new(vec![constants::CHILD_MARKETS_TAG]),
+            markets_by_category: LookupMap::new(vec![constants::MARKETS_BY_CATEGORY_TAG]),
+            unfinalized_markets: UnorderedSet::new(vec![constants::UNFINALIZED_MARKETS_TAG]),
+            finalized_markets: Vector::new(vec![constants::FINALIZED_MARKETS_TAG]),
         }
     }
 }
\ No newline at end of file
@@ -2,12 +2,14 @@
 use crate::*;
 use near_sdk::serde_json::json;
 use outcome_token::MintableToken;
+use crate::reward_program::RewardProgram;
 
 #[derive(serde::Serialize)]
 pub enum TransactionType {
     Buy,
     Sell,
     Redeem,
+    MintCompleteSet,
     ClaimEarnings,
     AddLiquidity,
     RemoveLiquidity,
@@ -47,6 +49,15 @@ pub fn log_pool(pool: &Pool) {
                 "collateral_denomination": U128(pool.collateral_denomination),
                 "total_withdrawn_fees": U128(pool.total_withdrawn_fees),
                 "fee_pool_weight": U128(pool.fee_pool_weight),
+                "pricing_model": pool.pricing_model,
+                "lmsr_liquidity": U128(pool.lmsr_liquidity),
+                "creator_fee_share": pool.creator_fee_share,
+                "creator_fees": U128(pool.creator_fees),
+                "fee_schedule": pool.fee_schedule.as_ref().map(|schedule| json!({
+                    "max_fee": U128(schedule.max_fee),
+                    "ramp_start": U64(schedule.ramp_start),
+                    "ramp_end": U64(schedule.ramp_end),
+                })),
                 "block_height": U64(env::block_index()),
 			}
 		})
@@ -131,7 +142,7 @@ enum SwapType {
     Buy,
 }
 
-fn log_swap(pool: &Pool, account_id: &AccountId, outcome: u16, input: u128, output: u128, fee: u128, swap_type: &SwapType) {
+fn log_swap(pool: &Pool, account_id: &AccountId, outcome: u16, input: u128, output: u128, fee: u128, protocol_fee: u128, creator_fee: u128, swap_type: &SwapType) {
     let swap_type_str = match swap_type {
         SwapType::Buy => "buy",
         SwapType::Sell => "sell",
@@ -148,6 +159,10 @@ fn log_swap(pool: &Pool, account_id: &AccountId, outcome: u16, input: u128, outp
                 "input": U128(input),
                 "output": U128(output),
                 "fee": U128(fee),
+                "lp_fee": U128(fee - protocol_fee - creator_fee),
+                "protocol_fee": U128(protocol_fee),
+                "creator_fee": U128(creator_fee),
+                "swap_fee": U128(pool.get_swap_fee()),
                 "collateral_token_id": pool.collateral_token_id,
                 "type": swap_type_str,
 			}
@@ -157,13 +172,13 @@ fn log_swap(pool: &Pool, account_id: &AccountId, outcome: u16, input: u128, outp
 	);
 }
 
-pub fn log_buy(pool: &Pool, account_id: &AccountId, outcome: u16, amount_in: u128, shares_out: u128, fee: u128) {
-    log_swap(pool, account_id, outcome, amount_in, shares_out, fee, &SwapType::Buy);
+pub fn log_buy(pool: &Pool, account_id: &AccountId, outcome: u16, amount_in: u128, shares_out: u128, fee: u128, protocol_fee: u128, creator_fee: u128) {
+    log_swap(pool, account_id, outcome, amount_in, shares_out, fee, protocol_fee, creator_fee, &SwapType::Buy);
     log_transaction(&TransactionType::Buy, account_id, amount_in, shares_out, U64(pool.id), Some(outcome));
 }
 
-pub fn log_sell(pool: &Pool, account_id: &AccountId, outcome: u16, shares_in: u128, amount_out: u128, fee: u128, to_escrow: u128) {
-    log_swap(pool, account_id, outcome, shares_in, amount_out - to_escrow, fee, &SwapType::Sell);
+pub fn log_sell(pool: &Pool, account_id: &AccountId, outcome: u16, shares_in: u128, amount_out: u128, fee: u128, protocol_fee: u128, creator_fee: u128, to_escrow: u128) {
+    log_swap(pool, account_id, outcome, shares_in, amount_out - to_escrow, fee, protocol_fee, creator_fee, &SwapType::Sell);
     log_transaction(&TransactionType::Sell, account_id, shares_in, amount_out - to_escrow, U64(pool.id), Some(outcome));
 }
 
@@ -188,15 +203,55 @@ pub fn log_user_balance(token: &MintableToken, account_id: &AccountId, new_balan
 	);
 }
 
+pub fn log_share_transfer(token: &MintableToken, sender_id: &AccountId, receiver_id: &AccountId, amount: u128) {
+    env::log(
+		json!({
+			"type": "share_transfers".to_string(),
+			"params": {
+                "pool_id": U64(token.pool_id),
+                "outcome_id": token.outcome_id,
+                "sender_id": sender_id,
+                "receiver_id": receiver_id,
+                "amount": U128(amount),
+                "block_height": U64(env::block_index()),
+                "date": U64(ns_to_ms(env::block_timestamp())),
+			}
+		})
+		.to_string()
+		.as_bytes()
+	);
+}
 
+// NEP-245 transfer event
+pub fn log_mt_transfer(old_owner_id: &AccountId, new_owner_id: &AccountId, token_ids: &[String], amounts: &[u128], memo: Option<String>) {
+    let amounts: Vec<U128> = amounts.iter().map(|amount| U128(*amount)).collect();
+    let mut data = json!({
+        "old_owner_id": old_owner_id,
+        "new_owner_id": new_owner_id,
+        "token_ids": token_ids,
+        "amounts": amounts,
+    });
+
+    if let Some(memo) = memo {
+        data["memo"] = json!(memo);
+    }
+
+    env::log(
+        format!(
+            "EVENT_JSON:{}",
+            json!({
+                "standard": "nep245",
+                "version": "1.0.0",
+                "event": "mt_transfer",
+                "data": [data]
+            })
+        )
+        .as_bytes()
+    );
+}
 
 // NEW_MARKET env log
-pub fn log_create_market(
-    market: &Market,
-    description: &String,  
-    extra_info: &String,  
-    categories: &Vec<String>,
-) {
+pub fn log_create_market(market: &Market) {
 	env::log(
 		json!({
             "type": "markets".to_string(),
@@ -204,17 +259,162 @@ pub fn log_create_market(
             "cap_id": format!("m_{}", market.pool.id),
 			"params": {
                 "id": U64(market.pool.id),
-                "description": description,
-                "extra_info": extra_info,
+                "description": market.description,
+                "extra_info": market.extra_info,
                 "outcome_tags": market.outcome_tags,
                 "end_time": U64(market.end_time),
                 "resolution_time": U64(market.resolution_time),
                 "finalized": market.finalized,
                 "payout_numerator": market.payout_numerator,
-                "categories": categories,
+                "categories": market.categories,
                 "creation_date": U64(ns_to_ms(env::block_timestamp())),
                 "enabled": market.enabled,
+                "cancelled": market.cancelled,
                 "is_scalar": market.is_scalar,
+                "scalar_payout": market.scalar_payout,
+                "creator": market.creator,
+                "parent": market.parent.as_ref().map(|parent| json!({
+                    "market_id": U64(parent.market_id),
+                    "outcome": parent.outcome,
+                })),
+			}
+		})
+		.to_string()
+		.as_bytes()
+	);
+}
+
+pub fn log_add_liquidity_zap(market_id: U64, account_id: &AccountId, total_in: u128, collateral_dust: u128, outcome_dust: &Vec<u128>) {
+    let outcome_dust: Vec<U128> = outcome_dust.iter().map(|dust| U128(*dust)).collect();
+    env::log(
+		json!({
+			"type": "add_liquidity_zap".to_string(),
+			"params": {
+                "market_id": market_id,
+                "account_id": account_id,
+                "total_in": U128(total_in),
+                "collateral_dust": U128(collateral_dust),
+                "outcome_dust": outcome_dust,
+                "block_height": U64(env::block_index()),
+			}
+		})
+		.to_string()
+		.as_bytes()
+	);
+}
+
+pub fn log_reward_program(market_id: U64, reward_program: &RewardProgram) {
+    env::log(
+		json!({
+			"type": "reward_programs".to_string(),
+			"params": {
+                "market_id": market_id,
+                "reward_token_id": reward_program.reward_token_id,
+                "reward_rate": U128(reward_program.reward_rate),
+                "start_time": U64(reward_program.start_time),
+                "end_time": U64(reward_program.end_time),
+                "block_height": U64(env::block_index()),
+			}
+		})
+		.to_string()
+		.as_bytes()
+	);
+}
+
+pub fn log_swept_rewards(market_id: U64, reward_token_id: &AccountId, amount: u128) {
+    env::log(
+		json!({
+			"type": "swept_rewards".to_string(),
+			"params": {
+                "market_id": market_id,
+                "reward_token_id": reward_token_id,
+                "amount": U128(amount),
+                "block_height": U64(env::block_index()),
+			}
+		})
+		.to_string()
+		.as_bytes()
+	);
+}
+
+pub fn log_claimed_rewards(market_id: U64, account_id: &AccountId, reward_token_id: &AccountId, amount: u128) {
+    env::log(
+		json!({
+			"type": "claimed_rewards".to_string(),
+			"params": {
+                "market_id": market_id,
+                "account_id": account_id,
+                "reward_token_id": reward_token_id,
+                "amount": U128(amount),
+                "block_height": U64(env::block_index()),
+			}
+		})
+		.to_string()
+		.as_bytes()
+	);
+}
+
+pub fn log_claimed_lp_fees(market_id: U64, account_id: &AccountId, amount: u128) {
+    env::log(
+		json!({
+			"type": "claimed_lp_fees".to_string(),
+			"params": {
+                "market_id": market_id,
+                "account_id": account_id,
+                "amount": U128(amount),
+                "block_height": U64(env::block_index()),
+			}
+		})
+		.to_string()
+		.as_bytes()
+	);
+}
+
+pub fn log_refunded_lp_fees(market_id: U64, account_id: &AccountId, amount: u128) {
+    env::log(
+		json!({
+			"type": "refunded_lp_fees".to_string(),
+			"params": {
+                "market_id": market_id,
+                "account_id": account_id,
+                "amount": U128(amount),
+                "block_height": U64(env::block_index()),
+			}
+		})
+		.to_string()
+		.as_bytes()
+	);
+}
+
+pub fn log_claimed_creator_fees(market: &Market, amount: u128) {
+    env::log(
+		json!({
+			"type": "claimed_creator_fees".to_string(),
+			"params": {
+                "market_id": U64(market.pool.id),
+                "creator": market.creator,
+                "amount": U128(amount),
+                "block_height": U64(env::block_index()),
+			}
+		})
+		.to_string()
+		.as_bytes()
+	);
+}
+
+pub fn log_market_status_with_reason(market: &Market, reason: &str) {
+    env::log(
+		json!({
+            "type": "markets".to_string(),
+            "action": "update",
+            "cap_id": format!("m_{}", market.pool.id),
+			"params": {
+                "payout_numerator": market.payout_numerator,
+                "finalized": market.finalized,
+                "enabled": market.enabled,
+                "cancelled": market.cancelled,
+                "awaiting_parent": market.awaiting_parent,
+                "reason": reason,
 			}
 		})
 		.to_string()
@@ -232,6 +432,8 @@ pub fn log_market_status(market: &Market) {
                 "payout_numerator": market.payout_numerator,
                 "finalized": market.finalized,
                 "enabled": market.enabled,
+                "cancelled": market.cancelled,
+                "awaiting_parent": market.awaiting_parent,
 			}
 		})
 		.to_string()
@@ -310,6 +512,22 @@ pub fn log_withdrawn_fees(pool_token: &MintableToken, account_id: &AccountId, wi
 	);
 }
 
+pub fn log_protocol_fees_withdrawal(collateral_token_id: &AccountId, treasury: &AccountId, amount: u128) {
+    env::log(
+		json!({
+			"type": "protocol_fee_withdrawals".to_string(),
+			"params": {
+                "collateral_token_id": collateral_token_id,
+                "treasury": treasury,
+                "amount": U128(amount),
+                "block_height": U64(env::block_index()),
+			}
+		})
+		.to_string()
+		.as_bytes()
+	);
+}
+
 pub fn log_account_outcome_spent(pool: &Pool, account_id: &AccountId, outcome_id: u16, spent: u128) {
     env::log(
 		json!({
@@ -1,7 +1,52 @@
 use crate::*;
+use crate::resolution_escrow::AccountMarkets;
+use crate::pool::Quote;
+use near_sdk::serde::de::{Deserializer, MapAccess, Visitor};
+
+#[ext_contract(ext_fees_resolver)]
+trait FeesResolver {
+    fn resolve_lp_fees_transfer(&mut self, account_id: AccountId, market_ids: Vec<U64>, amounts: Vec<WrappedBalance>) -> bool;
+}
+
+/**
+ * @notice the market outcome a conditional market depends on
+ */
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ParentMarket {
+    pub market_id: u64, // id of the parent market
+    pub outcome: u16, // outcome of the parent market that has to win for the conditional market to resolve on its own answer
+}
+
+/**
+ * @notice how a scalar market pays out, the `outcome_tags` of a scalar market are ordered values
+ */
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum ScalarPayout {
+    Linear, // the payout is split linearly between the two outcomes whose values are nearest to the answer
+    Bucket, // each outcome is a bucket starting at its value, the bucket containing the answer receives the full payout
+}
+
+impl Default for ScalarPayout {
+    fn default() -> Self {
+        ScalarPayout::Linear
+    }
+}
+
+/**
+ * @notice the lifecycle stage of a market, `Open` and `Ended` are derived from the market's `end_time`
+ */
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum MarketStatus {
+    Open, // trading is possible
+    Ended, // trading has stopped and the market awaits its resolution
+    Finalized, // the market has a resolution
+}
 
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct Market {
+    pub description: String, // Detailed description of the market
+    pub extra_info: String, // Extra information on how the market should be resoluted
+    pub categories: Vec<String>, // Categories to filter the market by
     pub end_time: Timestamp, // Time when trading is halted
     pub resolution_time: Timestamp, // Time when the market can be resoluted
     pub pool: Pool, // Implementation that manages the liquidity pool and swap
@@ -9,7 +54,173 @@ pub struct Market {
     pub payout_numerator: Option<Vec<U128>>, // Optional Vector that dictates how payout is done. Each payout numerator index corresponds to an outcome and shares the denomination of te collateral token for this market.
     pub finalized: bool, // If true the market has an outcome, if false the market it still undecided.
     pub enabled: bool, // If false the market is disabled for interaction.
+    pub cancelled: bool, // If true the market was cancelled by `gov` and finalized as invalid, claims are possible even if the market is disabled
     pub is_scalar: bool, // If true the market is scalar, false for categorical
+    pub scalar_payout: ScalarPayout, // How a scalar market pays out between its ordered `outcome_tags`
+    pub creator: AccountId, // The account that created the market and receives the creator fees
+    pub parent: Option<ParentMarket>, // If set the market is conditional on the outcome of another market
+    pub awaiting_parent: bool, // If true the market has been resolved but waits for its parent market to finalize, the resolution is stored in `payout_numerator`
+}
+
+/**
+ * @notice the outcome tags and weights of a weighted categorical answer in the order they're listed
+ *         unlike a map this keeps duplicate tags so they can be rejected instead of silently overwriting each other
+ */
+struct AnswerWeights(Vec<(String, u64)>);
+
+impl<'de> Deserialize<'de> for AnswerWeights {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct AnswerWeightsVisitor;
+
+        impl<'de> Visitor<'de> for AnswerWeightsVisitor {
+            type Value = AnswerWeights;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a JSON object of outcome tags to weights")
+            }
+
+            fn visit_map<M: MapAccess<'de>>(self, mut map: M) -> Result<Self::Value, M::Error> {
+                let mut weights = vec![];
+                while let Some(entry) = map.next_entry::<String, u64>()? {
+                    weights.push(entry);
+                }
+                Ok(AnswerWeights(weights))
+            }
+        }
+
+        deserializer.deserialize_map(AnswerWeightsVisitor)
+    }
+}
+
+impl Market {
+    /**
+     * @notice maps a categorical answer to a payout numerator, the answer is either:
+     *         a single outcome tag which receives the full payout,
+     *         a JSON list of outcome tags e.g. `["A","B"]` that split the payout equally,
+     *         or a JSON object of outcome tags to weights e.g. `{"A":3,"B":1}` that split the payout pro-rata
+     * @param answer is the answer provided by the oracle
+     * @returns the payout numerator indexed by outcome, summing up to the collateral denomination
+     */
+    pub fn get_categorical_payout_numerator(&self, answer: &str) -> Vec<U128> {
+        let outcome_index = |tag: &str| self.outcome_tags.iter().position(|outcome_tag| outcome_tag == tag).expect("ERR_OUTCOME_NOT_IN_TAGS");
+
+        // An exact match takes precedence so tags that happen to be valid JSON keep working
+        let winners: Vec<(usize, u128)> = if let Some(index) = self.outcome_tags.iter().position(|tag| tag == answer) {
+            vec![(index, 1)]
+        } else if let Ok(tags) = serde_json::from_str::<Vec<String>>(answer) {
+            tags.iter().map(|tag| (outcome_index(tag), 1)).collect()
+        } else if let Ok(AnswerWeights(weights)) = serde_json::from_str::<AnswerWeights>(answer) {
+            weights.iter().map(|(tag, weight)| (outcome_index(tag), u128::from(*weight))).collect()
+        } else {
+            panic!("ERR_OUTCOME_NOT_IN_TAGS");
+        };
+
+        let mut weights = vec![0; self.outcome_tags.len()];
+        for (index, weight) in winners {
+            assert!(weight > 0, "ERR_INVALID_WEIGHT");
+            assert_eq!(weights[index], 0, "ERR_DUPLICATE_OUTCOME");
+            weights[index] = weight;
+        }
+
+        math::weighted_payout(&weights, self.pool.collateral_denomination).into_iter().map(U128).collect()
+    }
+
+    /**
+     * @param outcome is the outcome to check
+     * @returns true if the market is finalized with `outcome` as its only winning outcome
+     */
+    pub fn outcome_won(&self, outcome: u16) -> bool {
+        match &self.payout_numerator {
+            Some(payout_numerator) => self.finalized && payout_numerator
+                .get(outcome as usize)
+                .map_or(false, |numerator| u128::from(*numerator) == self.pool.collateral_denomination),
+            None => false
+        }
+    }
+
+    /**
+     * @param now is the current time in ms
+     * @returns the lifecycle stage of the market at `now`
+     */
+    pub fn get_status(&self, now: Timestamp) -> MarketStatus {
+        if self.finalized {
+            MarketStatus::Finalized
+        } else if now >= self.end_time {
+            MarketStatus::Ended
+        } else {
+            MarketStatus::Open
+        }
+    }
+
+    /**
+     * @param market_id is the id of the market
+     * @param now is the current time in ms
+     * @returns the public representation of the market
+     */
+    pub fn to_view(&self, market_id: u64, now: Timestamp) -> MarketView {
+        MarketView {
+            id: U64(market_id),
+            description: self.description.to_string(),
+            extra_info: self.extra_info.to_string(),
+            categories: self.categories.clone(),
+            outcome_tags: self.outcome_tags.clone(),
+            end_time: U64(self.end_time),
+            resolution_time: U64(self.resolution_time),
+            collateral_token_id: self.pool.collateral_token_id.to_string(),
+            is_scalar: self.is_scalar,
+            scalar_payout: self.scalar_payout,
+            enabled: self.enabled,
+            cancelled: self.cancelled,
+            finalized: self.finalized,
+            awaiting_parent: self.awaiting_parent,
+            payout_numerator: self.payout_numerator.clone(),
+            creator: self.creator.to_string(),
+            parent: self.parent.as_ref().map(|parent| ParentMarketArgs {
+                market_id: U64(parent.market_id),
+                outcome: parent.outcome
+            }),
+            status: self.get_status(now),
+        }
+    }
+}
+
+/**
+ * @notice an account's position in a market
+ */
+#[derive(Serialize, Deserialize)]
+pub struct AccountPosition {
+    pub market_id: U64,
+    pub outcome_balances: Vec<WrappedBalance>, // outcome token balances indexed by outcome
+    pub pool_token_balance: WrappedBalance,
+    pub fees_withdrawable: WrappedBalance,
+    pub spent: Vec<WrappedBalance>, // collateral spent on each outcome, indexed by outcome
+    pub escrow_valid: WrappedBalance, // collateral that's paid out if the market resolves as valid
+    pub escrow_invalid: WrappedBalance, // collateral that's paid out if the market resolves as invalid
+}
+
+/**
+ * @notice public representation of a `Market`
+ */
+#[derive(Serialize, Deserialize)]
+pub struct MarketView {
+    pub id: U64,
+    pub description: String,
+    pub extra_info: String,
+    pub categories: Vec<String>,
+    pub outcome_tags: Vec<String>,
+    pub end_time: WrappedTimestamp,
+    pub resolution_time: WrappedTimestamp,
+    pub collateral_token_id: AccountId,
+    pub is_scalar: bool,
+    pub scalar_payout: ScalarPayout,
+    pub enabled: bool,
+    pub cancelled: bool,
+    pub finalized: bool,
+    pub awaiting_parent: bool,
+    pub payout_numerator: Option<Vec<U128>>,
+    pub creator: AccountId,
+    pub parent: Option<ParentMarketArgs>,
+    pub status: MarketStatus,
 }
 
 #[near_bindgen]
@@ -114,6 +325,57 @@ impl AMMContract {
         U128(market.pool.calc_buy_amount(collateral_in.into(), outcome_target))
     }
 
+    /**
+     * @notice calculates the amount of collateral a user has to put in in order to get `shares_out`
+     * @param market_id is the index of the market to retrieve data from
+     * @param shares_out is the amount of `outcome_shares` the user wants to buy
+     * @param outcome_target is the outcome that is to be purchased
+     * @returns a wrapped amount of collateral (including fees) a user would have to transfer in in order to get `shares_out`
+     */
+    pub fn calc_buy_collateral_in(
+        &self,
+        market_id: U64,
+        shares_out: WrappedBalance,
+        outcome_target: u16
+    ) -> WrappedBalance {
+        let market = self.get_market_expect(market_id);
+        U128(market.pool.calc_buy_collateral_in(shares_out.into(), outcome_target))
+    }
+
+    /**
+     * @notice simulates a purchase without changing the pool
+     * @param market_id is the index of the market to retrieve data from
+     * @param collateral_in is the amount of collateral to spend, fees included
+     * @param outcome_target is the outcome that is to be purchased
+     * @returns the shares out, fee, average price, price impact and the spot prices after the purchase
+     */
+    pub fn quote_buy(
+        &self,
+        market_id: U64,
+        collateral_in: WrappedBalance,
+        outcome_target: u16
+    ) -> Quote {
+        let market = self.get_market_expect(market_id);
+        market.pool.quote_buy(collateral_in.into(), outcome_target)
+    }
+
+    /**
+     * @notice simulates a sale without changing the pool
+     * @param market_id is the index of the market to retrieve data from
+     * @param collateral_out is the amount of collateral to receive, fees excluded
+     * @param outcome_target is the outcome that is to be sold
+     * @returns the shares in, fee, average price, price impact and the spot prices after the sale
+     */
+    pub fn quote_sell(
+        &self,
+        market_id: U64,
+        collateral_out: WrappedBalance,
+        outcome_target: u16
+    ) -> Quote {
+        let market = self.get_market_expect(market_id);
+        market.pool.quote_sell(collateral_out.into(), outcome_target)
+    }
+
     /**
      * @notice calculates the amount of shares a user has to put in in order to get `collateral_out`
      * @param market_id is the index of the market to retrieve data from
@@ -131,6 +393,23 @@ impl AMMContract {
         U128(market.pool.calc_sell_collateral_out(collateral_out.into(), outcome_target))
     }
 
+    /**
+     * @notice calculates the amount of collateral a user would get out for selling `shares_in`
+     * @param market_id is the index of the market to retrieve data from
+     * @param shares_in is the amount of `outcome_shares` the user wants to sell
+     * @param outcome_target is the outcome that is to be sold
+     * @returns a wrapped amount of collateral a user would get out for `shares_in`
+     */
+    pub fn calc_sell_shares_to_collateral(
+        &self,
+        market_id: U64,
+        shares_in: WrappedBalance,
+        outcome_target: u16
+    ) -> WrappedBalance {
+        let market = self.get_market_expect(market_id);
+        U128(market.pool.calc_sell_shares_to_collateral(shares_in.into(), outcome_target))
+    }
+
     /**
      * @param account_id is the `AccountId` to retrieve the `outcome_shares` for
      * @param market_id is the index of the market to retrieve data from
@@ -161,6 +440,152 @@ impl AMMContract {
         U128(market.pool.get_fees_withdrawable(account_id))
     }
 
+    /**
+     * @param market_id is the index of the market to retrieve data from
+     * @returns the `AccountId` that created the market
+     */
+    pub fn get_market_creator(&self, market_id: U64) -> AccountId {
+        self.get_market_expect(market_id).creator
+    }
+
+    /**
+     * @param market_id is the index of the market to retrieve data from
+     * @returns wrapped amount of creator fees that can be claimed by the market creator
+     */
+    pub fn get_creator_fees(&self, market_id: U64) -> WrappedBalance {
+        let market = self.get_market_expect(market_id);
+        U128(market.pool.creator_fees)
+    }
+
+    /**
+     * @param creator is the `AccountId` to list the created markets for
+     * @param from_index is the index in the creator's list of markets to start from
+     * @param limit is the maximum amount of market ids to return
+     * @returns the ids of the markets `creator` created, in order of creation
+     */
+    pub fn get_markets_by_creator(
+        &self,
+        creator: ValidAccountId,
+        from_index: u64,
+        limit: u64
+    ) -> Vec<U64> {
+        match self.markets_by_creator.get(creator.as_ref()) {
+            Some(creator_markets) => (from_index..std::cmp::min(from_index.saturating_add(limit), creator_markets.len()))
+                .map(|i| U64(creator_markets.get(i).unwrap()))
+                .collect(),
+            None => vec![]
+        }
+    }
+
+    /**
+     * @param market_id is the index of the market to retrieve data from
+     * @returns the market's details and status
+     */
+    pub fn get_market(&self, market_id: U64) -> MarketView {
+        self.get_market_expect(market_id).to_view(market_id.into(), ns_to_ms(env::block_timestamp()))
+    }
+
+    /**
+     * @param from_index is the market id to start from
+     * @param limit is the maximum amount of markets to return
+     * @returns the markets starting at `from_index`, in order of creation
+     */
+    pub fn get_markets(
+        &self,
+        from_index: u64,
+        limit: u64
+    ) -> Vec<MarketView> {
+        let now = ns_to_ms(env::block_timestamp());
+        (from_index..std::cmp::min(from_index.saturating_add(limit), self.markets.len()))
+            .map(|market_id| self.markets.get(market_id).unwrap().to_view(market_id, now))
+            .collect()
+    }
+
+    /**
+     * @param category is the category to list the markets for
+     * @param from_index is the index in the category's list of markets to start from
+     * @param limit is the maximum amount of market ids to return
+     * @returns the ids of the markets in `category`, in order of creation
+     */
+    pub fn get_markets_by_category(
+        &self,
+        category: String,
+        from_index: u64,
+        limit: u64
+    ) -> Vec<U64> {
+        match self.markets_by_category.get(&category) {
+            Some(category_markets) => (from_index..std::cmp::min(from_index.saturating_add(limit), category_markets.len()))
+                .map(|i| U64(category_markets.get(i).unwrap()))
+                .collect(),
+            None => vec![]
+        }
+    }
+
+    /**
+     * @param status is the lifecycle stage to list the markets for
+     * @param from_index is the index in the list of finalized markets to start from if `status` is `Finalized`, otherwise the index in the list of unfinalized markets
+     * @param limit is the maximum amount of markets to check, for `Open` and `Ended` fewer ids can be returned since markets in the other status are skipped
+     * @returns the ids of the markets that are in `status`, finalized markets are listed in order of finalization
+     */
+    pub fn get_markets_by_status(
+        &self,
+        status: MarketStatus,
+        from_index: u64,
+        limit: u64
+    ) -> Vec<U64> {
+        if status == MarketStatus::Finalized {
+            return (from_index..std::cmp::min(from_index.saturating_add(limit), self.finalized_markets.len()))
+                .map(|i| U64(self.finalized_markets.get(i).unwrap()))
+                .collect();
+        }
+
+        // Whether an unfinalized market is open or ended depends on the current time so it can't be indexed, only the requested page is checked to keep the call bounded
+        let now = ns_to_ms(env::block_timestamp());
+        let market_ids = self.unfinalized_markets.as_vector();
+        (from_index..std::cmp::min(from_index.saturating_add(limit), market_ids.len()))
+            .map(|i| market_ids.get(i).unwrap())
+            .filter(|market_id| self.markets.get(*market_id).unwrap().get_status(now) == status)
+            .map(U64)
+            .collect()
+    }
+
+    /**
+     * @param account_id is the account to return the portfolio of
+     * @param from_index is the index in the account's list of markets to start from
+     * @param limit is the maximum amount of positions to return
+     * @returns the account's positions in the markets it holds an escrow in, a market is removed from the list once its earnings are claimed which changes the order of the list
+     */
+    pub fn get_account_portfolio(
+        &self,
+        account_id: ValidAccountId,
+        from_index: u64,
+        limit: u64
+    ) -> Vec<AccountPosition> {
+        let account_markets = match AccountMarkets::new().get(account_id.as_ref()) {
+            Some(account_markets) => account_markets,
+            None => return vec![]
+        };
+
+        let market_ids = account_markets.as_vector();
+        (from_index..std::cmp::min(from_index.saturating_add(limit), market_ids.len()))
+            .map(|i| {
+                let market_id = market_ids.get(i).unwrap();
+                self.get_market_expect(U64(market_id)).pool.get_account_position(account_id.as_ref())
+            })
+            .collect()
+    }
+
+    /**
+     * @param market_id is the index of the market to retrieve data from
+     * @returns the ids of the conditional markets that depend on the market
+     */
+    pub fn get_child_markets(&self, market_id: U64) -> Vec<U64> {
+        match self.child_markets.get(&market_id.into()) {
+            Some(child_markets) => child_markets.iter().map(U64).collect(),
+            None => vec![]
+        }
+    }
+
     /**
      * @notice sell `outcome_shares` for collateral
      * @param market_id references the market to sell shares from 
@@ -179,29 +604,87 @@ impl AMMContract {
     ) -> Promise {
         self.assert_unpaused();
         let initial_storage = env::storage_usage();
-        let collateral_out: u128 = collateral_out.into();
+        let mut market = self.markets.get(market_id.into()).expect("ERR_NO_MARKET");
+
+        let promise = self.sell_internal(&mut market, collateral_out.into(), outcome_target, max_shares_in.into());
+
+        self.markets.replace(market_id.into(), &market);
+        helper::refund_storage(initial_storage, env::predecessor_account_id());
+
+        promise
+    }
+
+    /**
+     * @notice sell an exact amount of `outcome_shares` for collateral
+     * @param market_id references the market to sell shares from 
+     * @param outcome_target is which `outcome_share` to sell
+     * @param shares_in is the amount of `outcome_shares` to sell
+     * @param min_collateral_out is the minimum amount of collateral the sender expects out, this is to prevent sandwich attacks and unwanted `slippage`
+     * @returns a promise referencing the collateral token transaction
+     */
+    #[payable]
+    pub fn sell_shares(
+        &mut self,
+        market_id: U64,
+        outcome_target: u16,
+        shares_in: WrappedBalance,
+        min_collateral_out: WrappedBalance
+    ) -> Promise {
+        self.assert_unpaused();
+        let initial_storage = env::storage_usage();
+        let mut market = self.markets.get(market_id.into()).expect("ERR_NO_MARKET");
+
+        let collateral_out = market.pool.calc_sell_shares_to_collateral(shares_in.into(), outcome_target);
+        assert!(collateral_out > 0, "ERR_ZERO_COLLATERAL_OUT");
+        assert!(collateral_out >= u128::from(min_collateral_out), "ERR_MIN_COLLATERAL_OUT");
+
+        let promise = self.sell_internal(&mut market, collateral_out, outcome_target, shares_in.into());
+
+        self.markets.replace(market_id.into(), &market);
+        helper::refund_storage(initial_storage, env::predecessor_account_id());
+
+        promise
+    }
+
+    /**
+     * @notice swaps `outcome_shares` of one outcome for another within the same pool, without collateral leaving the contract
+     * @param market_id references the market to swap shares in
+     * @param outcome_in is which `outcome_share` to sell
+     * @param outcome_out is which `outcome_share` to buy
+     * @param shares_in is the amount of `outcome_in` shares to swap
+     * @param min_shares_out is the minimum amount of `outcome_out` shares to receive, this is to prevent sandwich attacks and unwanted `slippage`
+     * @returns the wrapped amount of `outcome_out` shares received
+     */
+    #[payable]
+    pub fn swap_outcomes(
+        &mut self,
+        market_id: U64,
+        outcome_in: u16,
+        outcome_out: u16,
+        shares_in: WrappedBalance,
+        min_shares_out: WrappedBalance
+    ) -> WrappedBalance {
+        self.assert_unpaused();
+        let initial_storage = env::storage_usage();
         let mut market = self.markets.get(market_id.into()).expect("ERR_NO_MARKET");
         assert!(market.enabled, "ERR_DISABLED_MARKET");
         assert!(!market.finalized, "ERR_FINALIZED_MARKET");
         assert!(market.end_time > ns_to_ms(env::block_timestamp()), "ERR_MARKET_ENDED");
-        let escrowed = market.pool.sell(
+
+        let shares_out = market.pool.swap_outcomes(
             &env::predecessor_account_id(),
-            collateral_out,
-            outcome_target,
-            max_shares_in.into()
+            outcome_in,
+            outcome_out,
+            shares_in.into(),
+            min_shares_out.into(),
+            self.protocol_fee_share
         );
+        self.collect_protocol_fees(&mut market);
 
         self.markets.replace(market_id.into(), &market);
         helper::refund_storage(initial_storage, env::predecessor_account_id());
 
-        collateral_token::ft_transfer(
-            env::predecessor_account_id(), 
-            U128(collateral_out - escrowed),
-            None,
-            &market.pool.collateral_token_id,
-            1,
-            GAS_BASE_COMPUTE
-        )
+        U128(shares_out)
     }
 
     /**
@@ -246,6 +729,52 @@ impl AMMContract {
         )
     }
 
+    /**
+     * @notice burns a complete set of outcome tokens held by the sender and transfers out an equal amount of collateral
+     * @param market_id references the market to merge outcome tokens in
+     * @param amount is the amount of outcome tokens of each outcome to merge
+     * @returns a transfer `Promise` or a boolean representing a successful merge if all collateral is escrowed
+     */
+    #[payable]
+    pub fn merge_complete_set(
+        &mut self,
+        market_id: U64,
+        amount: WrappedBalance
+    ) -> PromiseOrValue<bool> {
+        self.assert_unpaused();
+        let initial_storage = env::storage_usage();
+
+        let mut market = self.markets.get(market_id.into()).expect("ERR_NO_MARKET");
+        assert!(market.enabled, "ERR_DISABLED_MARKET");
+        assert!(!market.finalized, "ERR_MARKET_FINALIZED");
+
+        let amount: u128 = amount.into();
+        let escrowed = market.pool.merge_complete_set(&env::predecessor_account_id(), amount);
+
+        self.markets.replace(market_id.into(), &market);
+
+        helper::refund_storage(initial_storage, env::predecessor_account_id());
+
+        let payout = amount - escrowed;
+
+        logger::log_transaction(&logger::TransactionType::Redeem, &env::predecessor_account_id(), amount, payout, market_id, None);
+
+        if payout > 0 {
+            PromiseOrValue::Promise(
+                collateral_token::ft_transfer(
+                    env::predecessor_account_id(),
+                    payout.into(),
+                    None,
+                    &market.pool.collateral_token_id,
+                    1,
+                    GAS_BASE_COMPUTE
+                )
+            )
+        } else {
+            PromiseOrValue::Value(true)
+        }
+    }
+
     /**
      * @notice removes liquidity from a pool
      * @param market_id references the market to remove liquidity from 
@@ -262,7 +791,7 @@ impl AMMContract {
         let initial_storage = env::storage_usage();
 
         let mut market = self.markets.get(market_id.into()).expect("ERR_NO_MARKET");
-        assert!(market.enabled, "ERR_DISABLED_MARKET");
+        assert!(market.enabled || market.cancelled, "ERR_DISABLED_MARKET");
 
         let fees_earned = market.pool.exit_pool(
             &env::predecessor_account_id(),
@@ -289,6 +818,150 @@ impl AMMContract {
         }
     }
 
+    /**
+     * @notice removes liquidity from a pool and pays it out in collateral, the outcome tokens that come out are merged into complete sets
+     *         and whatever can't be merged is sold back into the pool as long as the market is trading
+     * @param market_id references the market to remove liquidity from
+     * @param lp_in is the amount of LP tokens to redeem
+     * @param min_collateral_out is the minimum amount of collateral the sender expects out, this is to prevent sandwich attacks and unwanted `slippage`
+     * @returns a transfer `Promise` or a boolean representing a successful exit if all collateral is escrowed
+     */
+    #[payable]
+    pub fn exit_pool_to_collateral(
+        &mut self,
+        market_id: U64,
+        lp_in: WrappedBalance,
+        min_collateral_out: WrappedBalance
+    ) -> PromiseOrValue<bool> {
+        self.assert_unpaused();
+        let initial_storage = env::storage_usage();
+
+        let mut market = self.markets.get(market_id.into()).expect("ERR_NO_MARKET");
+        assert!(market.enabled, "ERR_DISABLED_MARKET");
+        assert!(!market.finalized, "ERR_MARKET_FINALIZED");
+
+        let sell_remaining = market.end_time > ns_to_ms(env::block_timestamp());
+        let collateral_out = market.pool.exit_pool_to_collateral(
+            &env::predecessor_account_id(),
+            lp_in.into(),
+            sell_remaining,
+            self.protocol_fee_share
+        );
+        assert!(collateral_out >= u128::from(min_collateral_out), "ERR_MIN_COLLATERAL_OUT");
+        self.collect_protocol_fees(&mut market);
+
+        self.markets.replace(market_id.into(), &market);
+
+        helper::refund_storage(initial_storage, env::predecessor_account_id());
+
+        if collateral_out > 0 {
+            PromiseOrValue::Promise(
+                collateral_token::ft_transfer(
+                    env::predecessor_account_id(),
+                    collateral_out.into(),
+                    None,
+                    &market.pool.collateral_token_id,
+                    1,
+                    GAS_BASE_COMPUTE
+                )
+            )
+        } else {
+            PromiseOrValue::Value(true)
+        }
+    }
+
+    /**
+     * @notice transfers the swap fees the sender accrued as an LP without exiting the pool
+     * @param market_id references the market to claim the LP fees of
+     * @returns a promise referencing the collateral token transaction
+     */
+    #[payable]
+    pub fn claim_lp_fees(
+        &mut self,
+        market_id: U64
+    ) -> Promise {
+        self.assert_unpaused();
+        let initial_storage = env::storage_usage();
+
+        let fees_earned = self.claim_lp_fees_internal(market_id, &env::predecessor_account_id());
+        assert!(fees_earned > 0, "ERR_NO_FEES");
+
+        helper::refund_storage(initial_storage, env::predecessor_account_id());
+
+        let market = self.get_market_expect(market_id);
+        self.transfer_lp_fees(&env::predecessor_account_id(), &market.pool.collateral_token_id, vec![market_id], vec![U128(fees_earned)])
+    }
+
+    /**
+     * @notice transfers the swap fees the sender accrued as an LP in multiple markets without exiting the pools
+     *         fees are bundled into a single transfer per collateral token
+     * @param market_ids references the markets to claim the LP fees of
+     * @returns the combined fee transfer promises, one per collateral token
+     */
+    #[payable]
+    pub fn claim_lp_fees_batch(
+        &mut self,
+        market_ids: Vec<U64>
+    ) -> Promise {
+        self.assert_unpaused();
+        let initial_storage = env::storage_usage();
+        let sender = env::predecessor_account_id();
+
+        // Maps every collateral token to the markets and amounts of fees claimed in that token
+        let mut fees_per_token: Vec<(AccountId, Vec<U64>, Vec<WrappedBalance>)> = vec![];
+        for market_id in market_ids {
+            let fees_earned = self.claim_lp_fees_internal(market_id, &sender);
+            if fees_earned > 0 {
+                let collateral_token_id = self.get_market_expect(market_id).pool.collateral_token_id;
+                match fees_per_token.iter_mut().find(|(token_id, _, _)| *token_id == collateral_token_id) {
+                    Some((_, token_market_ids, amounts)) => {
+                        token_market_ids.push(market_id);
+                        amounts.push(U128(fees_earned));
+                    },
+                    None => fees_per_token.push((collateral_token_id, vec![market_id], vec![U128(fees_earned)]))
+                }
+            }
+        }
+
+        helper::refund_storage(initial_storage, sender.to_string());
+
+        fees_per_token.into_iter()
+            .map(|(collateral_token_id, token_market_ids, amounts)| self.transfer_lp_fees(&sender, &collateral_token_id, token_market_ids, amounts))
+            .fold(None, |promises: Option<Promise>, promise| match promises {
+                Some(promises) => Some(promises.and(promise)),
+                None => Some(promise)
+            })
+            .expect("ERR_NO_FEES")
+    }
+
+    /**
+     * @notice re-credits the LP fees of a failed fee transfer in the markets they were claimed from so they can be claimed again
+     * @param account_id is the account the fees were transferred to
+     * @param market_ids references the markets the fees were claimed from
+     * @param amounts is the amount of fees claimed from each market in the same order as `market_ids`
+     * @returns whether the fee transfer succeeded
+     */
+    pub fn resolve_lp_fees_transfer(
+        &mut self,
+        account_id: AccountId,
+        market_ids: Vec<U64>,
+        amounts: Vec<WrappedBalance>
+    ) -> bool {
+        assert_self();
+        let transferred = is_promise_success();
+
+        if !transferred {
+            for (market_id, amount) in market_ids.into_iter().zip(amounts.into_iter()) {
+                let mut market = self.get_market_expect(market_id);
+                market.pool.refund_fees(&account_id, amount.into());
+                self.markets.replace(market_id.into(), &market);
+                logger::log_refunded_lp_fees(market_id, &account_id, amount.into());
+            }
+        }
+
+        transferred
+    }
+
     /**
      * @notice sets the resolution and finalizes a market
      * @param market_id references the market to resolute 
@@ -316,12 +989,52 @@ impl AMMContract {
             None => ()
         };
 
-        market.payout_numerator = payout_numerator;
-        market.finalized = true;
-        self.markets.replace(market_id.into(), &market);
+        self.finalize_market(market_id.into(), market, payout_numerator);
         // helper::refund_storage(initial_storage, env::predecessor_account_id());
+    }
 
-        logger::log_market_status(&market);
+    /**
+     * @notice disables a market which halts all interaction with it, can only be called by `gov`
+     * @param market_id references the market to disable
+     * @param reason describes why the market is disabled
+     */
+    pub fn disable_market(
+        &mut self,
+        market_id: U64,
+        reason: String
+    ) {
+        self.assert_gov();
+        let mut market = self.markets.get(market_id.into()).expect("ERR_NO_MARKET");
+        assert!(market.enabled, "ERR_DISABLED_MARKET");
+        assert!(!market.finalized, "ERR_IS_FINALIZED");
+
+        market.enabled = false;
+        self.markets.replace(market_id.into(), &market);
+
+        logger::log_market_status_with_reason(&market, &reason);
+    }
+
+    /**
+     * @notice cancels a market by finalizing it as invalid so every participant can reclaim their basis through `claim_earnings`, can only be called by `gov`
+     *         trading stays halted since the market is finalized, LPs can exit and claim even if the market was disabled
+     * @param market_id references the market to cancel
+     * @param reason describes why the market is cancelled
+     */
+    pub fn cancel_market(
+        &mut self,
+        market_id: U64,
+        reason: String
+    ) {
+        self.assert_gov();
+        let mut market = self.markets.get(market_id.into()).expect("ERR_NO_MARKET");
+        assert!(!market.finalized, "ERR_IS_FINALIZED");
+
+        // A conditional market can't wait for its parent once cancelled
+        market.parent = None;
+        market.cancelled = true;
+        self.finalize_market(market_id.into(), market, None);
+
+        logger::log_market_status_with_reason(&self.get_market_expect(market_id), &reason);
     }
 
     pub fn set_outcome(&mut self, requestor: AccountId, outcome: Outcome, tags: Option<Vec<U64>>) {
@@ -333,42 +1046,35 @@ impl AMMContract {
         let market_id = parsed_tags.get(0).unwrap();
         let mut market = self.get_market_expect(*market_id);
 
+        // Markets can be finalized before the oracle answers, through cancellation or a parent resolving against a conditional market, late answers are ignored
+        if market.finalized {
+            return;
+        }
+
         match outcome {
             Outcome::Answer(answer) => {
                 if market.is_scalar {
-                    // f64 due the uncertainty of the pointer value/bounds
-                    // It could be a decimal value or it could be an int/uint. 
-                    // f64 can handle both for now
-                    let lower_bound: f64 = market.outcome_tags.get(0).unwrap().parse().unwrap();
-                    let upper_bound: f64 = market.outcome_tags.get(1).unwrap().parse().unwrap();
-
-                    let pointer_value = clamp_f64(answer.parse().unwrap(), lower_bound, upper_bound);
-                    let range = upper_bound - lower_bound;
-                    let percentage_upper_bound = (upper_bound - pointer_value) / range;
-
-                    // Convert to string and back to u128 due to conversion errors
-                    let payout_short_str = (percentage_upper_bound * market.pool.collateral_denomination as f64).round().to_string();
-                    let payout_short: u128 = payout_short_str.parse().unwrap();
-
-                    market.payout_numerator = Some(vec![
-                        U128(payout_short),
-                        U128(market.pool.collateral_denomination - payout_short),
-                    ]);
+                    // The bounds and the answer can be integers or decimals, they're parsed into fixed-point numbers to interpolate without precision loss
+                    let bounds: Vec<math::FixedDecimal> = market.outcome_tags
+                        .iter()
+                        .map(|tag| math::FixedDecimal::parse(tag, constants::SCALAR_DECIMALS))
+                        .collect();
+                    let pointer_value = math::FixedDecimal::parse(&answer, constants::SCALAR_DECIMALS);
+
+                    let payout_numerator = match market.scalar_payout {
+                        ScalarPayout::Linear => math::scalar_payout_linear(&bounds, &pointer_value, market.pool.collateral_denomination),
+                        ScalarPayout::Bucket => math::scalar_payout_bucket(&bounds, &pointer_value, market.pool.collateral_denomination)
+                    };
+                    market.payout_numerator = Some(payout_numerator.into_iter().map(U128).collect());
                 } else {
-                    // Categorical market where only 1 outcome can be the winner
-                    let index = market.outcome_tags.iter().position(|tag| tag == &answer).expect("ERR_OUTCOME_NOT_IN_TAGS");
-                    let mut payout_numerator = vec![U128(0); market.outcome_tags.len()];
-
-                    payout_numerator[index] = U128(market.pool.collateral_denomination);
-                    market.payout_numerator = Some(payout_numerator);
+                    market.payout_numerator = Some(market.get_categorical_payout_numerator(&answer));
                 }
             },
             Outcome::Invalid => market.payout_numerator = None,
         }
 
-        market.finalized = true;
-        self.markets.replace(market_id.0, &market);
-        logger::log_market_status(&market);
+        let payout_numerator = market.payout_numerator.take();
+        self.finalize_market(market_id.0, market, payout_numerator);
     }
 
     /**
@@ -383,7 +1089,7 @@ impl AMMContract {
         self.assert_unpaused();
         let initial_storage = env::storage_usage();
         let mut market = self.markets.get(market_id.into()).expect("ERR_NO_MARKET");
-        assert!(market.enabled, "ERR_DISABLED_MARKET");
+        assert!(market.enabled || market.cancelled, "ERR_DISABLED_MARKET");
         assert!(market.finalized, "ERR_NOT_FINALIZED");
 
         let payout = market.pool.payout(&env::predecessor_account_id(), &market.payout_numerator);
@@ -410,6 +1116,35 @@ impl AMMContract {
             panic!("ERR_NO_PAYOUT");
         }
     }
+
+    /**
+     * @notice transfers the creator fees a market accrued to its creator, only callable by the creator
+     * @param market_id references the market to claim the creator fees of
+     * @returns a promise referencing the collateral token transaction
+     */
+    pub fn claim_creator_fees(
+        &mut self,
+        market_id: U64
+    ) -> Promise {
+        self.assert_unpaused();
+        let mut market = self.markets.get(market_id.into()).expect("ERR_NO_MARKET");
+        assert_eq!(env::predecessor_account_id(), market.creator, "ERR_NOT_CREATOR");
+
+        let creator_fees = market.pool.take_creator_fees();
+        assert!(creator_fees > 0, "ERR_NO_CREATOR_FEES");
+        self.markets.replace(market_id.into(), &market);
+
+        logger::log_claimed_creator_fees(&market, creator_fees);
+
+        collateral_token::ft_transfer(
+            market.creator.to_string(),
+            creator_fees.into(),
+            None,
+            &market.pool.collateral_token_id,
+            1,
+            GAS_BASE_COMPUTE
+        )
+    }
 }
 
 impl AMMContract {
@@ -421,6 +1156,146 @@ impl AMMContract {
         self.markets.get(market_id.into()).expect("ERR_NO_MARKET")
     }
 
+    /**
+     * @notice finalizes a market with `payout_numerator`, a conditional market whose parent isn't finalized yet stores its resolution until the parent finalizes
+     *         and resolves as invalid if its parent outcome didn't win. Once a market is finalized the conditional markets that depend on it are finalized as well
+     * @param market_id is the id of the market to finalize
+     * @param market is the market to finalize
+     * @param payout_numerator is the resolution of the market, `None` if the market is invalid
+     */
+    fn finalize_market(&mut self, market_id: u64, mut market: Market, payout_numerator: Option<Vec<U128>>) {
+        market.payout_numerator = payout_numerator;
+
+        if let Some(parent) = &market.parent {
+            let parent_market = self.get_market_expect(U64(parent.market_id));
+            if !parent_market.finalized {
+                market.awaiting_parent = true;
+                self.markets.replace(market_id, &market);
+                logger::log_market_status(&market);
+                return;
+            }
+
+            if !parent_market.outcome_won(parent.outcome) {
+                market.payout_numerator = None;
+            }
+        }
+
+        market.awaiting_parent = false;
+        market.finalized = true;
+        self.markets.replace(market_id, &market);
+        self.unfinalized_markets.remove(&market_id);
+        self.finalized_markets.push(&market_id);
+        logger::log_market_status(&market);
+
+        let child_market_ids: Vec<u64> = match self.child_markets.get(&market_id) {
+            Some(child_markets) => child_markets.to_vec(),
+            None => vec![]
+        };
+
+        for child_market_id in child_market_ids {
+            let mut child_market = self.get_market_expect(U64(child_market_id));
+            if child_market.finalized {
+                continue;
+            }
+
+            let parent_outcome = child_market.parent.as_ref().expect("ERR_NO_PARENT").outcome;
+            if !market.outcome_won(parent_outcome) {
+                self.finalize_market(child_market_id, child_market, None);
+            } else if child_market.awaiting_parent {
+                let child_payout_numerator = child_market.payout_numerator.take();
+                self.finalize_market(child_market_id, child_market, child_payout_numerator);
+            }
+        }
+    }
+
+    /**
+     * @notice marks the LP fees `account_id` accrued in a market as withdrawn, the caller is responsible for transferring them
+     * @param market_id references the market to claim the LP fees of
+     * @param account_id is the LP to claim the fees for
+     * @returns the amount of fees claimed
+     */
+    /**
+     * @notice transfers LP fees to `account_id` and re-credits them in `resolve_lp_fees_transfer` if the transfer fails
+     * @param account_id is the account to transfer the fees to
+     * @param collateral_token_id is the collateral token of every market in `market_ids`
+     * @param market_ids references the markets the fees were withdrawn from
+     * @param amounts is the amount of fees withdrawn from each market in the same order as `market_ids`
+     * @returns a promise that resolves to whether the fees were transferred
+     */
+    pub fn transfer_lp_fees(
+        &self,
+        account_id: &AccountId,
+        collateral_token_id: &AccountId,
+        market_ids: Vec<U64>,
+        amounts: Vec<WrappedBalance>
+    ) -> Promise {
+        let total_fees = amounts.iter().fold(0, |total, amount| total + u128::from(*amount));
+
+        collateral_token::ft_transfer(
+            account_id.to_string(),
+            total_fees.into(),
+            None,
+            collateral_token_id,
+            1,
+            GAS_BASE_COMPUTE
+        ).then(ext_fees_resolver::resolve_lp_fees_transfer(
+            account_id.to_string(),
+            market_ids,
+            amounts,
+            &env::current_account_id(),
+            0,
+            GAS_BASE_COMPUTE
+        ))
+    }
+
+    fn claim_lp_fees_internal(&mut self, market_id: U64, account_id: &AccountId) -> Balance {
+        let mut market = self.markets.get(market_id.into()).expect("ERR_NO_MARKET");
+        assert!(market.enabled || market.cancelled, "ERR_DISABLED_MARKET");
+
+        let fees_earned = market.pool.withdraw_fees(account_id);
+        self.markets.replace(market_id.into(), &market);
+        logger::log_claimed_lp_fees(market_id, account_id, fees_earned);
+
+        fees_earned
+    }
+
+    /**
+     * @notice sells `outcome_shares` from the predecessor and transfers the collateral out that's not escrowed
+     * @param market the market to sell shares from, the caller is responsible for storing it afterwards
+     * @param collateral_out is the amount of collateral that is expected to be transferred to the sender after selling
+     * @param outcome_target is which `outcome_share` to sell
+     * @param max_shares_in is the maximum amount of `outcome_shares` to transfer in
+     * @returns a promise referencing the collateral token transaction
+     */
+    fn sell_internal(
+        &mut self,
+        market: &mut Market,
+        collateral_out: u128,
+        outcome_target: u16,
+        max_shares_in: u128
+    ) -> Promise {
+        assert!(market.enabled, "ERR_DISABLED_MARKET");
+        assert!(!market.finalized, "ERR_FINALIZED_MARKET");
+        assert!(market.end_time > ns_to_ms(env::block_timestamp()), "ERR_MARKET_ENDED");
+        let escrowed = market.pool.sell(
+            &env::predecessor_account_id(),
+            collateral_out,
+            outcome_target,
+            max_shares_in,
+            self.protocol_fee_share
+        );
+        self.collect_protocol_fees(market);
+
+        collateral_token::ft_transfer(
+            env::predecessor_account_id(), 
+            U128(collateral_out - escrowed),
+            None,
+            &market.pool.collateral_token_id,
+            1,
+            GAS_BASE_COMPUTE
+        )
+    }
+
     /**
      * @notice add liquidity to a pool
      * @param sender the sender of the original transfer_call
@@ -459,34 +1334,136 @@ impl AMMContract {
         PromiseOrValue::Value(0.into())
     }
 
+    /**
+     * @notice add liquidity to a pool without receiving outcome tokens, the ones `add_liquidity` would return are sold back into the pool
+     * @param sender the sender of the original transfer_call
+     * @param total_in total amount of collateral to add to the market
+     * @param json string of `AddLiquidityZap` args
+     * @returns the collateral that couldn't be added, which is refunded to the sender
+     */
+    pub fn add_liquidity_zap(
+        &mut self,
+        sender: &AccountId,
+        total_in: u128,
+        args: AddLiquidityZapArgs,
+    ) -> PromiseOrValue<U128> {
+        let weights_u128: Option<Vec<u128>> = args.weight_indication.map(|weight_indication| {
+            weight_indication
+                .iter()
+                .map(|weight| { u128::from(*weight) })
+                .collect()
+        });
+
+        let mut market = self.markets.get(args.market_id.into()).expect("ERR_NO_MARKET");
+        assert!(market.enabled, "ERR_DISABLED_MARKET");
+        assert!(!market.finalized, "ERR_FINALIZED_MARKET");
+        assert!(market.end_time > ns_to_ms(env::block_timestamp()), "ERR_MARKET_ENDED");
+        assert_collateral_token(&market.pool.collateral_token_id);
+
+        let (collateral_dust, outcome_dust) = market.pool.add_liquidity_zap(
+            &sender,
+            total_in,
+            weights_u128,
+            self.protocol_fee_share
+        );
+        self.collect_protocol_fees(&mut market);
+        self.markets.replace(args.market_id.into(), &market);
+
+        logger::log_add_liquidity_zap(args.market_id, &sender, total_in, collateral_dust, &outcome_dust);
+        PromiseOrValue::Value(U128(collateral_dust))
+    }
 
     /**
      * @notice buy an outcome token
      * @param sender the sender of the original transfer_call
-     * @param total_in total amount of collateral to use for purchasing
-     * @param json string of `AddLiquidity` args
+     * @param total_in total amount of collateral to use for purchasing
+     * @param json string of `AddLiquidity` args
+     */
+    pub fn buy(
+        &mut self,
+        sender: &AccountId,
+        collateral_in: u128, 
+        args: BuyArgs,
+    ) -> PromiseOrValue<U128> {
+        let mut market = self.markets.get(args.market_id.into()).expect("ERR_NO_MARKET");
+        assert!(market.enabled, "ERR_DISABLED_MARKET");
+        assert!(!market.finalized, "ERR_FINALIZED_MARKET");
+        assert!(market.end_time > ns_to_ms(env::block_timestamp()), "ERR_MARKET_ENDED");
+        assert_collateral_token(&market.pool.collateral_token_id);
+        
+        market.pool.buy(
+            &sender,
+            collateral_in,
+            args.outcome_target,
+            args.min_shares_out.into(),
+            self.protocol_fee_share
+        );
+        self.collect_protocol_fees(&mut market);
+
+        self.markets.replace(args.market_id.into(), &market);
+        PromiseOrValue::Value(0.into())
+    }
+
+    /**
+     * @notice mint a complete set of outcome tokens, one of each outcome for every unit of collateral
+     * @param sender the sender of the original transfer_call
+     * @param total_in total amount of collateral to mint outcome tokens for
+     * @param json string of `MintCompleteSetArgs` args
+     */
+    pub fn mint_complete_set(
+        &mut self,
+        sender: &AccountId,
+        total_in: u128,
+        args: MintCompleteSetArgs,
+    ) -> PromiseOrValue<U128> {
+        let mut market = self.markets.get(args.market_id.into()).expect("ERR_NO_MARKET");
+        assert!(market.enabled, "ERR_DISABLED_MARKET");
+        assert!(!market.finalized, "ERR_FINALIZED_MARKET");
+        assert!(market.end_time > ns_to_ms(env::block_timestamp()), "ERR_MARKET_ENDED");
+        assert_collateral_token(&market.pool.collateral_token_id);
+
+        market.pool.mint_complete_set(&sender, total_in);
+        logger::log_transaction(&logger::TransactionType::MintCompleteSet, &sender, total_in, total_in, args.market_id, None);
+
+        self.markets.replace(args.market_id.into(), &market);
+        PromiseOrValue::Value(0.into())
+    }
+
+    /**
+     * @notice buy an exact amount of outcome tokens, the collateral that's not needed for the purchase is returned to the sender
+     * @param sender the sender of the original transfer_call
+     * @param total_in total amount of collateral transferred in
+     * @param json string of `BuyExactSharesArgs` args
+     * @returns the amount of collateral that was not used and should be refunded by the collateral token
      */
-    pub fn buy(
+    pub fn buy_exact_shares(
         &mut self,
         sender: &AccountId,
-        collateral_in: u128, 
-        args: BuyArgs,
+        total_in: u128,
+        args: BuyExactSharesArgs,
     ) -> PromiseOrValue<U128> {
         let mut market = self.markets.get(args.market_id.into()).expect("ERR_NO_MARKET");
         assert!(market.enabled, "ERR_DISABLED_MARKET");
         assert!(!market.finalized, "ERR_FINALIZED_MARKET");
         assert!(market.end_time > ns_to_ms(env::block_timestamp()), "ERR_MARKET_ENDED");
         assert_collateral_token(&market.pool.collateral_token_id);
-        
+
+        let shares_out: u128 = args.shares_out.into();
+        let collateral_in = market.pool.calc_buy_collateral_in(shares_out, args.outcome_target);
+        assert!(collateral_in <= u128::from(args.max_collateral_in), "ERR_MAX_COLLATERAL_IN");
+        assert!(collateral_in <= total_in, "ERR_INSUFFICIENT_COLLATERAL_IN");
+
         market.pool.buy(
             &sender,
             collateral_in,
             args.outcome_target,
-            args.min_shares_out.into()
+            shares_out,
+            self.protocol_fee_share
         );
+        self.collect_protocol_fees(&mut market);
 
         self.markets.replace(args.market_id.into(), &market);
-        PromiseOrValue::Value(0.into())
+        PromiseOrValue::Value(U128(total_in - collateral_in))
     }
 }
 
@@ -558,6 +1535,7 @@ mod market_basic_tests {
         );
 
         contract.create_market(
+            &alice(),
             &CreateMarketArgs {
                 description: empty_string(), // market description
                 extra_info: empty_string(), // extra info
@@ -571,8 +1549,63 @@ mod market_basic_tests {
                 swap_fee: (10_u128.pow(24) / 50).into(), // swap fee, 2%
                 challenge_period: U64(1),
                 is_scalar: false, // is_scalar,
+                pricing_model: None, // pricing_model
+                fee_schedule: None, // fee_schedule
+                creator_fee: None, // creator_fee
+                parent: None, // parent
+                scalar_payout: None // scalar_payout
+            }
+        );
+    }
+
+    #[test]
+    fn fee_schedule_ramps_towards_end_time() {
+        testing_env!(get_context(alice(), 0));
+
+        let mut contract = AMMContract::init(
+            bob().try_into().unwrap(),
+            vec![collateral_whitelist::Token{account_id: token(), decimals: 24}],
+            oracle().try_into().unwrap()
+        );
+
+        let end_time = 1609951265967;
+        let ramp_duration = 86_400_000; // 1 day
+        let market_id = contract.create_market(
+            &alice(),
+            &CreateMarketArgs {
+                description: empty_string(), // market description
+                extra_info: empty_string(), // extra info
+                outcomes: 2, // outcomes
+                outcome_tags: empty_string_vec(2), // outcome tags
+                categories: empty_string_vec(2), // categories
+                end_time: end_time.into(), // end_time
+                resolution_time: 1619882574000.into(), // resolution_time (~1 day after end_time)
+                sources: vec![Source{end_point: "test".to_string(), source_path: "test".to_string()}],
+                collateral_token_id: token(), // collateral_token_id
+                swap_fee: (10_u128.pow(24) / 100).into(), // swap fee, 1%
+                challenge_period: U64(1),
+                is_scalar: false, // is_scalar,
+                pricing_model: None, // pricing_model
+                fee_schedule: Some(FeeScheduleArgs {
+                    max_fee: (10_u128.pow(24) / 20).into(), // 5%
+                    ramp_duration: U64(ramp_duration)
+                }),
+                creator_fee: None, // creator_fee
+                parent: None, // parent
+                scalar_payout: None // scalar_payout
             }
         );
+
+        assert_eq!(contract.get_pool_swap_fee(market_id), U128(10_u128.pow(24) / 100));
+
+        testing_env!(get_context(alice(), ms_to_ns(end_time - ramp_duration)));
+        assert_eq!(contract.get_pool_swap_fee(market_id), U128(10_u128.pow(24) / 100));
+
+        testing_env!(get_context(alice(), ms_to_ns(end_time - ramp_duration / 2)));
+        assert_eq!(contract.get_pool_swap_fee(market_id), U128(3 * 10_u128.pow(24) / 100));
+
+        testing_env!(get_context(alice(), ms_to_ns(end_time)));
+        assert_eq!(contract.get_pool_swap_fee(market_id), U128(10_u128.pow(24) / 20));
     }
 
     #[test]
@@ -587,6 +1620,7 @@ mod market_basic_tests {
         );
 
         let market_id = contract.create_market(
+            &alice(),
             &CreateMarketArgs {
                 description: empty_string(), // market description
                 extra_info: empty_string(), // extra info
@@ -599,7 +1633,12 @@ mod market_basic_tests {
                 collateral_token_id: token(), // collateral_token_id
                 swap_fee: (10_u128.pow(24) / 50).into(), // swap fee, 2%
                 challenge_period: U64(1),
-                is_scalar: false // is_scalar
+                is_scalar: false, // is_scalar,
+                pricing_model: None, // pricing_model
+                fee_schedule: None, // fee_schedule
+                creator_fee: None, // creator_fee
+                parent: None, // parent
+                scalar_payout: None // scalar_payout
             }
         );
 
@@ -633,6 +1672,7 @@ mod market_basic_tests {
         );
 
         contract.create_market(
+            &alice(),
             &CreateMarketArgs {
                 description: empty_string(), // market description
                 extra_info: empty_string(), // extra info
@@ -645,7 +1685,12 @@ mod market_basic_tests {
                 collateral_token_id: token(), // collateral_token_id
                 swap_fee: (10_u128.pow(24) / 50).into(), // swap fee, 2%
                 challenge_period: U64(1),
-                is_scalar: false // is_scalar
+                is_scalar: false, // is_scalar,
+                pricing_model: None, // pricing_model
+                fee_schedule: None, // fee_schedule
+                creator_fee: None, // creator_fee
+                parent: None, // parent
+                scalar_payout: None // scalar_payout
             }
         );
     }
@@ -661,6 +1706,7 @@ mod market_basic_tests {
         );
         
         contract.create_market(
+            &alice(),
             &CreateMarketArgs {
                 description: empty_string(), // market description
                 extra_info: empty_string(), // extra info
@@ -674,6 +1720,11 @@ mod market_basic_tests {
                 swap_fee: (10_u128.pow(24) / 50).into(), // swap fee, 2%
                 challenge_period: U64(1),
                 is_scalar: false, // is_scalar,
+                pricing_model: None, // pricing_model
+                fee_schedule: None, // fee_schedule
+                creator_fee: None, // creator_fee
+                parent: None, // parent
+                scalar_payout: None // scalar_payout
             }
         );
 
@@ -685,6 +1736,82 @@ mod market_basic_tests {
         assert_eq!(market.payout_numerator, None, "Numerator should be None");
     }
 
+    fn categorical_market_args(parent: Option<ParentMarketArgs>) -> CreateMarketArgs {
+        CreateMarketArgs {
+            description: empty_string(), // market description
+            extra_info: empty_string(), // extra info
+            outcomes: 2, // outcomes
+            outcome_tags: vec!["YES".to_string(), "NO".to_string()], // outcome tags
+            categories: empty_string_vec(2), // categories
+            end_time: 1609951265967.into(), // end_time
+            resolution_time: 1619882574000.into(), // resolution_time (~1 day after end_time)
+            sources: vec![Source{end_point: "test".to_string(), source_path: "test".to_string()}],
+            collateral_token_id: token(), // collateral_token_id
+            swap_fee: (10_u128.pow(24) / 50).into(), // swap fee, 2%
+            challenge_period: U64(1),
+            is_scalar: false, // is_scalar,
+            pricing_model: None, // pricing_model
+            fee_schedule: None, // fee_schedule
+            creator_fee: None, // creator_fee
+            parent, // parent
+            scalar_payout: None // scalar_payout
+        }
+    }
+
+    #[test]
+    fn conditional_market_waits_for_parent() {
+        testing_env!(get_context(oracle(), 0));
+
+        let mut contract = AMMContract::init(
+            bob().try_into().unwrap(),
+            vec![collateral_whitelist::Token{account_id: token(), decimals: 24}],
+            oracle().try_into().unwrap()
+        );
+
+        let parent_id = contract.create_market(&alice(), &categorical_market_args(None));
+        let child_id = contract.create_market(&alice(), &categorical_market_args(Some(ParentMarketArgs { market_id: parent_id, outcome: 0 })));
+        assert_eq!(contract.get_child_markets(parent_id), vec![child_id]);
+
+        // The child's answer is stored until the parent finalizes
+        contract.set_outcome(alice(), Outcome::Answer("NO".to_string()), Some(vec![child_id]));
+        let child = contract.get_market_expect(child_id);
+        assert!(!child.finalized, "Child should wait for its parent");
+        assert!(child.awaiting_parent, "Child should wait for its parent");
+
+        contract.set_outcome(alice(), Outcome::Answer("YES".to_string()), Some(vec![parent_id]));
+        let child = contract.get_market_expect(child_id);
+        assert!(child.finalized, "Child should be finalized");
+        assert_eq!(child.payout_numerator, Some(vec![U128(0), U128(10_u128.pow(24))]));
+    }
+
+    #[test]
+    fn conditional_market_invalid_on_other_parent_outcome() {
+        testing_env!(get_context(oracle(), 0));
+
+        let mut contract = AMMContract::init(
+            bob().try_into().unwrap(),
+            vec![collateral_whitelist::Token{account_id: token(), decimals: 24}],
+            oracle().try_into().unwrap()
+        );
+
+        let parent_id = contract.create_market(&alice(), &categorical_market_args(None));
+        let child_id = contract.create_market(&alice(), &categorical_market_args(Some(ParentMarketArgs { market_id: parent_id, outcome: 0 })));
+        let grandchild_id = contract.create_market(&alice(), &categorical_market_args(Some(ParentMarketArgs { market_id: child_id, outcome: 1 })));
+
+        contract.set_outcome(alice(), Outcome::Answer("NO".to_string()), Some(vec![parent_id]));
+
+        // The child and the market that depends on it are invalid without waiting for their own answers
+        for market_id in vec![child_id, grandchild_id] {
+            let market = contract.get_market_expect(market_id);
+            assert!(market.finalized, "Market should be finalized");
+            assert_eq!(market.payout_numerator, None, "Numerator should be None");
+        }
+
+        // Late answers are ignored
+        contract.set_outcome(alice(), Outcome::Answer("NO".to_string()), Some(vec![child_id]));
+        assert_eq!(contract.get_market_expect(child_id).payout_numerator, None);
+    }
+
     #[test]
     fn valid_categorical_outcome() {
         testing_env!(get_context(oracle(), 0));
@@ -696,6 +1823,7 @@ mod market_basic_tests {
         );
         
         contract.create_market(
+            &alice(),
             &CreateMarketArgs {
                 description: empty_string(), // market description
                 extra_info: empty_string(), // extra info
@@ -709,6 +1837,11 @@ mod market_basic_tests {
                 swap_fee: (10_u128.pow(24) / 50).into(), // swap fee, 2%
                 challenge_period: U64(1),
                 is_scalar: false, // is_scalar,
+                pricing_model: None, // pricing_model
+                fee_schedule: None, // fee_schedule
+                creator_fee: None, // creator_fee
+                parent: None, // parent
+                scalar_payout: None // scalar_payout
             }
         );
 
@@ -719,6 +1852,291 @@ mod market_basic_tests {
         assert_eq!(market.payout_numerator, Some(vec![U128(0), U128(1000000000000000000000000)]), "Numerator should be set");
     }
 
+    #[test]
+    #[should_panic(expected = "ERR_DISABLED_MARKET")]
+    fn disabled_market_halts_trading() {
+        testing_env!(get_context(bob(), 0));
+
+        let mut contract = AMMContract::init(
+            bob().try_into().unwrap(),
+            vec![collateral_whitelist::Token{account_id: token(), decimals: 24}],
+            oracle().try_into().unwrap()
+        );
+
+        let market_id = contract.create_market(&alice(), &categorical_market_args(None));
+        let mut market = contract.get_market_expect(market_id);
+        market.enabled = true;
+        contract.markets.replace(market_id.into(), &market);
+
+        contract.disable_market(market_id, "broken sources".to_string());
+        assert!(!contract.get_market_expect(market_id).enabled, "Market should be disabled");
+
+        contract.add_liquidity(&alice(), 10_u128.pow(24), AddLiquidityArgs {
+            market_id,
+            weight_indication: Some(vec![U128(1), U128(1)])
+        });
+    }
+
+    #[test]
+    fn cancelled_market_is_invalid() {
+        testing_env!(get_context(bob(), 0));
+
+        let mut contract = AMMContract::init(
+            bob().try_into().unwrap(),
+            vec![collateral_whitelist::Token{account_id: token(), decimals: 24}],
+            oracle().try_into().unwrap()
+        );
+
+        let market_id = contract.create_market(&alice(), &categorical_market_args(None));
+        let child_id = contract.create_market(&alice(), &categorical_market_args(Some(ParentMarketArgs { market_id, outcome: 0 })));
+        contract.cancel_market(market_id, "malicious market".to_string());
+
+        for market_id in vec![market_id, child_id] {
+            let market = contract.get_market_expect(market_id);
+            assert!(market.finalized, "Market should be finalized");
+            assert_eq!(market.payout_numerator, None, "Numerator should be None");
+        }
+        assert!(contract.get_market_expect(market_id).cancelled, "Market should be cancelled");
+        assert!(!contract.get_market_expect(market_id).enabled, "Cancelling shouldn't enable the market");
+
+        // A late oracle answer doesn't overwrite the cancellation
+        testing_env!(get_context(oracle(), 0));
+        contract.set_outcome(alice(), Outcome::Answer("YES".to_string()), Some(vec![market_id]));
+        assert_eq!(contract.get_market_expect(market_id).payout_numerator, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NO_GOVERNANCE_ADDRESS")]
+    fn cancel_market_without_gov() {
+        testing_env!(get_context(alice(), 0));
+
+        let mut contract = AMMContract::init(
+            bob().try_into().unwrap(),
+            vec![collateral_whitelist::Token{account_id: token(), decimals: 24}],
+            oracle().try_into().unwrap()
+        );
+
+        let market_id = contract.create_market(&alice(), &categorical_market_args(None));
+        contract.cancel_market(market_id, "malicious market".to_string());
+    }
+
+    #[test]
+    fn set_protocol_fee_share_up_to_max() {
+        testing_env!(get_context(bob(), 0));
+
+        let mut contract = AMMContract::init(
+            bob().try_into().unwrap(),
+            vec![collateral_whitelist::Token{account_id: token(), decimals: 24}],
+            oracle().try_into().unwrap()
+        );
+
+        contract.set_protocol_fee_share(constants::MAX_PROTOCOL_FEE_SHARE);
+        assert_eq!(contract.get_protocol_fee_share(), constants::MAX_PROTOCOL_FEE_SHARE);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INVALID_PROTOCOL_FEE_SHARE")]
+    fn set_protocol_fee_share_above_max() {
+        testing_env!(get_context(bob(), 0));
+
+        let mut contract = AMMContract::init(
+            bob().try_into().unwrap(),
+            vec![collateral_whitelist::Token{account_id: token(), decimals: 24}],
+            oracle().try_into().unwrap()
+        );
+
+        contract.set_protocol_fee_share(constants::MAX_PROTOCOL_FEE_SHARE + 1);
+    }
+
+    #[test]
+    fn market_views_and_indexes() {
+        testing_env!(get_context(oracle(), 0));
+
+        let mut contract = AMMContract::init(
+            bob().try_into().unwrap(),
+            vec![collateral_whitelist::Token{account_id: token(), decimals: 24}],
+            oracle().try_into().unwrap()
+        );
+
+        let mut args = categorical_market_args(None);
+        args.description = "Will it rain?".to_string();
+        args.categories = vec!["weather".to_string(), "weather".to_string()];
+        let weather_id = contract.create_market(&alice(), &args);
+        args.categories = vec!["sports".to_string()];
+        let sports_id = contract.create_market(&alice(), &args);
+
+        let market = contract.get_market(weather_id);
+        assert_eq!(market.description, "Will it rain?");
+        assert_eq!(market.outcome_tags, vec!["YES".to_string(), "NO".to_string()]);
+        assert_eq!(market.status, MarketStatus::Open);
+        assert_eq!(contract.get_markets(1, 10).iter().map(|market| market.id).collect::<Vec<U64>>(), vec![sports_id]);
+
+        assert_eq!(contract.get_markets_by_category("weather".to_string(), 0, 10), vec![weather_id]);
+        assert_eq!(contract.get_markets_by_category("sports".to_string(), 0, 10), vec![sports_id]);
+        assert_eq!(contract.get_markets_by_status(MarketStatus::Open, 0, 10).len(), 2);
+        assert_eq!(contract.get_markets_by_status(MarketStatus::Open, 1, u64::MAX).len(), 1);
+        assert_eq!(contract.get_markets(1, u64::MAX).len(), 1);
+
+        contract.set_outcome(alice(), Outcome::Answer("YES".to_string()), Some(vec![weather_id]));
+        assert_eq!(contract.get_markets_by_status(MarketStatus::Finalized, 0, 10), vec![weather_id]);
+        assert_eq!(contract.get_markets_by_status(MarketStatus::Open, 0, 10), vec![sports_id]);
+
+        testing_env!(get_context(oracle(), ms_to_ns(1609951265967)));
+        assert_eq!(contract.get_markets_by_status(MarketStatus::Ended, 0, 10), vec![sports_id]);
+        assert!(contract.get_markets_by_status(MarketStatus::Open, 0, 10).is_empty());
+        assert_eq!(contract.get_market(sports_id).status, MarketStatus::Ended);
+    }
+
+    #[test]
+    fn account_portfolio_tracks_positions() {
+        let one = 10_u128.pow(24);
+        testing_env!(get_context(token(), 0));
+
+        let mut contract = AMMContract::init(
+            bob().try_into().unwrap(),
+            vec![collateral_whitelist::Token{account_id: token(), decimals: 24}],
+            oracle().try_into().unwrap()
+        );
+
+        let market_id = contract.create_market(&alice(), &categorical_market_args(None));
+        let mut market = contract.get_market_expect(market_id);
+        market.enabled = true;
+        contract.markets.replace(market_id.into(), &market);
+
+        contract.add_liquidity(&alice(), one, AddLiquidityArgs {
+            market_id,
+            weight_indication: Some(vec![U128(1), U128(1)])
+        });
+        contract.buy(&bob(), one / 10, BuyArgs {
+            market_id,
+            outcome_target: 0,
+            min_shares_out: U128(0)
+        });
+
+        let alice_portfolio = contract.get_account_portfolio(alice().try_into().unwrap(), 0, 10);
+        assert_eq!(alice_portfolio.len(), 1);
+        assert!(u128::from(alice_portfolio[0].pool_token_balance) > 0);
+
+        let bob_portfolio = contract.get_account_portfolio(bob().try_into().unwrap(), 0, 10);
+        assert_eq!(bob_portfolio.len(), 1);
+        assert_eq!(bob_portfolio[0].market_id, market_id);
+        assert!(u128::from(bob_portfolio[0].outcome_balances[0]) > 0);
+        assert!(u128::from(bob_portfolio[0].spent[0]) > 0);
+        assert_eq!(bob_portfolio[0].outcome_balances[1], U128(0));
+        assert_eq!(bob_portfolio[0].pool_token_balance, U128(0));
+
+        testing_env!(get_context(oracle(), 0));
+        contract.set_outcome(alice(), Outcome::Answer("YES".to_string()), Some(vec![market_id]));
+
+        // Claiming the earnings removes the market from the portfolio
+        testing_env!(get_context(bob(), 0));
+        contract.claim_earnings(market_id);
+        assert!(contract.get_account_portfolio(bob().try_into().unwrap(), 0, 10).is_empty());
+    }
+
+    #[test]
+    fn quote_sell_matches_sell() {
+        let one = 10_u128.pow(24);
+        testing_env!(get_context(token(), 0));
+
+        let mut contract = AMMContract::init(
+            bob().try_into().unwrap(),
+            vec![collateral_whitelist::Token{account_id: token(), decimals: 24}],
+            oracle().try_into().unwrap()
+        );
+
+        let market_id = contract.create_market(&alice(), &categorical_market_args(None));
+        let mut market = contract.get_market_expect(market_id);
+        market.enabled = true;
+        contract.markets.replace(market_id.into(), &market);
+
+        contract.add_liquidity(&alice(), one, AddLiquidityArgs {
+            market_id,
+            weight_indication: Some(vec![U128(1), U128(1)])
+        });
+        contract.buy(&bob(), one / 10, BuyArgs {
+            market_id,
+            outcome_target: 0,
+            min_shares_out: U128(0)
+        });
+
+        testing_env!(get_context(bob(), 0));
+        let collateral_out = one / 20;
+        let quote = contract.quote_sell(market_id, U128(collateral_out), 0);
+        assert!(u128::from(quote.fee) > 0, "Quote should include the swap fee");
+        assert_eq!(u128::from(quote.average_price), math::simple_div_u128(one, collateral_out, quote.shares.into()));
+
+        let shares_before = u128::from(contract.get_share_balance(&bob(), market_id, 0));
+        let fee_pool_weight_before = contract.get_market_expect(market_id).pool.fee_pool_weight;
+        contract.sell(market_id, U128(collateral_out), 0, quote.shares);
+
+        let shares_after = u128::from(contract.get_share_balance(&bob(), market_id, 0));
+        assert_eq!(shares_before - shares_after, u128::from(quote.shares));
+        assert_eq!(contract.get_market_expect(market_id).pool.fee_pool_weight - fee_pool_weight_before, u128::from(quote.fee));
+        for outcome in 0..2 {
+            assert_eq!(contract.get_spot_price_sans_fee(market_id, outcome), quote.spot_prices_after[outcome as usize]);
+        }
+    }
+
+    #[test]
+    fn valid_categorical_tie_and_weighted_outcome() {
+        let one = 10_u128.pow(24);
+        testing_env!(get_context(oracle(), 0));
+
+        let mut contract = AMMContract::init(
+            bob().try_into().unwrap(),
+            vec![collateral_whitelist::Token{account_id: token(), decimals: 24}],
+            oracle().try_into().unwrap()
+        );
+
+        let mut args = categorical_market_args(None);
+        args.outcomes = 3;
+        args.outcome_tags = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+        let tie_id = contract.create_market(&alice(), &args);
+        let weighted_id = contract.create_market(&alice(), &args);
+        let uneven_tie_id = contract.create_market(&alice(), &args);
+
+        contract.set_outcome(alice(), Outcome::Answer("[\"A\",\"C\"]".to_string()), Some(vec![tie_id]));
+        assert_eq!(contract.get_market_expect(tie_id).payout_numerator, Some(vec![U128(one / 2), U128(0), U128(one / 2)]));
+
+        contract.set_outcome(alice(), Outcome::Answer("{\"B\":3,\"C\":1}".to_string()), Some(vec![weighted_id]));
+        assert_eq!(contract.get_market_expect(weighted_id).payout_numerator, Some(vec![U128(0), U128(one * 3 / 4), U128(one / 4)]));
+
+        // The rounding remainder goes to the first winner so the payout adds up to the collateral denomination
+        contract.set_outcome(alice(), Outcome::Answer("[\"A\",\"B\",\"C\"]".to_string()), Some(vec![uneven_tie_id]));
+        assert_eq!(contract.get_market_expect(uneven_tie_id).payout_numerator, Some(vec![U128(one / 3 + 1), U128(one / 3), U128(one / 3)]));
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_DUPLICATE_OUTCOME")]
+    fn invalid_categorical_duplicate_winners() {
+        testing_env!(get_context(oracle(), 0));
+
+        let mut contract = AMMContract::init(
+            bob().try_into().unwrap(),
+            vec![collateral_whitelist::Token{account_id: token(), decimals: 24}],
+            oracle().try_into().unwrap()
+        );
+
+        let market_id = contract.create_market(&alice(), &categorical_market_args(None));
+        contract.set_outcome(alice(), Outcome::Answer("[\"YES\",\"YES\"]".to_string()), Some(vec![market_id]));
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_DUPLICATE_OUTCOME")]
+    fn invalid_categorical_duplicate_weighted_winners() {
+        testing_env!(get_context(oracle(), 0));
+
+        let mut contract = AMMContract::init(
+            bob().try_into().unwrap(),
+            vec![collateral_whitelist::Token{account_id: token(), decimals: 24}],
+            oracle().try_into().unwrap()
+        );
+
+        let market_id = contract.create_market(&alice(), &categorical_market_args(None));
+        contract.set_outcome(alice(), Outcome::Answer("{\"YES\":1,\"NO\":1,\"YES\":3}".to_string()), Some(vec![market_id]));
+    }
+
     #[test]
     fn valid_scalar_large_range() {
         testing_env!(get_context(oracle(), 0));
@@ -730,6 +2148,7 @@ mod market_basic_tests {
         );
         
         contract.create_market(
+            &alice(),
             &CreateMarketArgs {
                 description: empty_string(), // market description
                 extra_info: empty_string(), // extra info
@@ -743,6 +2162,11 @@ mod market_basic_tests {
                 swap_fee: (10_u128.pow(24) / 50).into(), // swap fee, 2%
                 challenge_period: U64(1),
                 is_scalar: true, // is_scalar,
+                pricing_model: None, // pricing_model
+                fee_schedule: None, // fee_schedule
+                creator_fee: None, // creator_fee
+                parent: None, // parent
+                scalar_payout: None // scalar_payout
             }
         );
 
@@ -764,6 +2188,7 @@ mod market_basic_tests {
         );
         
         contract.create_market(
+            &alice(),
             &CreateMarketArgs {
                 description: empty_string(), // market description
                 extra_info: empty_string(), // extra info
@@ -777,6 +2202,11 @@ mod market_basic_tests {
                 swap_fee: (10_u128.pow(24) / 50).into(), // swap fee, 2%
                 challenge_period: U64(1),
                 is_scalar: true, // is_scalar,
+                pricing_model: None, // pricing_model
+                fee_schedule: None, // fee_schedule
+                creator_fee: None, // creator_fee
+                parent: None, // parent
+                scalar_payout: None // scalar_payout
             }
         );
 
@@ -798,6 +2228,7 @@ mod market_basic_tests {
         );
         
         contract.create_market(
+            &alice(),
             &CreateMarketArgs {
                 description: empty_string(), // market description
                 extra_info: empty_string(), // extra info
@@ -811,6 +2242,11 @@ mod market_basic_tests {
                 swap_fee: (10_u128.pow(24) / 50).into(), // swap fee, 2%
                 challenge_period: U64(1),
                 is_scalar: true, // is_scalar,
+                pricing_model: None, // pricing_model
+                fee_schedule: None, // fee_schedule
+                creator_fee: None, // creator_fee
+                parent: None, // parent
+                scalar_payout: None // scalar_payout
             }
         );
 
@@ -832,6 +2268,7 @@ mod market_basic_tests {
         );
         
         contract.create_market(
+            &alice(),
             &CreateMarketArgs {
                 description: empty_string(), // market description
                 extra_info: empty_string(), // extra info
@@ -845,6 +2282,11 @@ mod market_basic_tests {
                 swap_fee: (10_u128.pow(24) / 50).into(), // swap fee, 2%
                 challenge_period: U64(1),
                 is_scalar: true, // is_scalar,
+                pricing_model: None, // pricing_model
+                fee_schedule: None, // fee_schedule
+                creator_fee: None, // creator_fee
+                parent: None, // parent
+                scalar_payout: None // scalar_payout
             }
         );
 
@@ -866,6 +2308,7 @@ mod market_basic_tests {
         );
         
         contract.create_market(
+            &alice(),
             &CreateMarketArgs {
                 description: empty_string(), // market description
                 extra_info: empty_string(), // extra info
@@ -879,6 +2322,11 @@ mod market_basic_tests {
                 swap_fee: (10_u128.pow(24) / 50).into(), // swap fee, 2%
                 challenge_period: U64(1),
                 is_scalar: true, // is_scalar,
+                pricing_model: None, // pricing_model
+                fee_schedule: None, // fee_schedule
+                creator_fee: None, // creator_fee
+                parent: None, // parent
+                scalar_payout: None // scalar_payout
             }
         );
 
@@ -889,6 +2337,105 @@ mod market_basic_tests {
         assert_eq!(market.payout_numerator, Some(vec![U128(0), U128(1000000000000000000000000)]), "Numerator should be set");
     }
 
+    fn scalar_market_args(bounds: &[&str], scalar_payout: Option<ScalarPayout>) -> CreateMarketArgs {
+        CreateMarketArgs {
+            description: empty_string(), // market description
+            extra_info: empty_string(), // extra info
+            outcomes: bounds.len() as u16, // outcomes
+            outcome_tags: bounds.iter().map(|bound| bound.to_string()).collect(), // outcome tags
+            categories: empty_string_vec(2), // categories
+            end_time: 1609951265967.into(), // end_time
+            resolution_time: 1619882574000.into(), // resolution_time (~1 day after end_time)
+            sources: vec![Source{end_point: "test".to_string(), source_path: "test".to_string()}],
+            collateral_token_id: token(), // collateral_token_id
+            swap_fee: (10_u128.pow(24) / 50).into(), // swap fee, 2%
+            challenge_period: U64(1),
+            is_scalar: true, // is_scalar,
+            pricing_model: None, // pricing_model
+            fee_schedule: None, // fee_schedule
+            creator_fee: None, // creator_fee
+            parent: None, // parent
+            scalar_payout // scalar_payout
+        }
+    }
+
+    fn resolve_scalar(bounds: &[&str], scalar_payout: Option<ScalarPayout>, answer: &str) -> Option<Vec<U128>> {
+        testing_env!(get_context(oracle(), 0));
+
+        let mut contract = AMMContract::init(
+            bob().try_into().unwrap(),
+            vec![collateral_whitelist::Token{account_id: token(), decimals: 24}],
+            oracle().try_into().unwrap()
+        );
+
+        let market_id = contract.create_market(&alice(), &scalar_market_args(bounds, scalar_payout));
+        contract.set_outcome(alice(), Outcome::Answer(answer.to_string()), Some(vec![market_id]));
+
+        let market = contract.get_market_expect(market_id);
+        assert!(market.finalized, "Market should be finalized");
+        market.payout_numerator
+    }
+
+    #[test]
+    fn valid_scalar_negative_bounds() {
+        let payout_numerator = resolve_scalar(&["-100", "-20"], None, "-40");
+        assert_eq!(payout_numerator, Some(vec![U128(250000000000000000000000), U128(750000000000000000000000)]), "Numerator should be set");
+    }
+
+    #[test]
+    fn valid_scalar_huge_bounds() {
+        let payout_numerator = resolve_scalar(&["0", "1000000000000000000000000000000"], None, "123456789012345678901234567890");
+        assert_eq!(payout_numerator, Some(vec![U128(876543210987654321098765), U128(123456789012345678901235)]), "Numerator should be set");
+    }
+
+    #[test]
+    fn valid_scalar_many_decimal_bounds() {
+        let payout_numerator = resolve_scalar(&["1.123456789012345678901234", "1.123456789012345678901238"], None, "1.123456789012345678901235");
+        assert_eq!(payout_numerator, Some(vec![U128(750000000000000000000000), U128(250000000000000000000000)]), "Numerator should be set");
+    }
+
+    #[test]
+    fn valid_range_linear_payout() {
+        let bands = ["0", "10000", "20000", "30000", "40000", "50000", "60000", "70000"];
+        let one = 10_u128.pow(24);
+
+        let payout_numerator = resolve_scalar(&bands, None, "25000");
+        assert_eq!(payout_numerator, Some(vec![U128(0), U128(0), U128(one / 2), U128(one / 2), U128(0), U128(0), U128(0), U128(0)]), "Numerator should be set");
+
+        let payout_numerator = resolve_scalar(&bands, None, "90000");
+        assert_eq!(payout_numerator, Some(vec![U128(0), U128(0), U128(0), U128(0), U128(0), U128(0), U128(0), U128(one)]), "Numerator should be set");
+    }
+
+    #[test]
+    fn valid_range_bucket_payout() {
+        let bands = ["0", "10000", "20000", "30000", "40000", "50000", "60000", "70000"];
+        let one = 10_u128.pow(24);
+
+        let payout_numerator = resolve_scalar(&bands, Some(ScalarPayout::Bucket), "25000");
+        assert_eq!(payout_numerator, Some(vec![U128(0), U128(0), U128(one), U128(0), U128(0), U128(0), U128(0), U128(0)]), "Numerator should be set");
+
+        let payout_numerator = resolve_scalar(&bands, Some(ScalarPayout::Bucket), "-5");
+        assert_eq!(payout_numerator, Some(vec![U128(one), U128(0), U128(0), U128(0), U128(0), U128(0), U128(0), U128(0)]), "Numerator should be set");
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INVALID_SCALAR_BOUNDS")]
+    fn invalid_range_bucket_order() {
+        resolve_scalar(&["0", "20000", "10000"], Some(ScalarPayout::Bucket), "5000");
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INVALID_DECIMAL")]
+    fn invalid_scalar_answer() {
+        resolve_scalar(&["0", "50"], None, "12abc");
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INVALID_SCALAR_BOUNDS")]
+    fn invalid_scalar_bounds() {
+        resolve_scalar(&["50", "0"], None, "25");
+    }
+
     // TODO: should be changed with oracle integration
     // #[test]
     // #[should_panic(expected = "ERR_RESOLUTION_TIME_NOT_REACHED")]
@@ -902,6 +2449,7 @@ mod market_basic_tests {
     //     );
 
     //     let market_id = contract.create_market(
+    //         &alice(),
     //         &CreateMarketArgs {
     //             description: empty_string(), // market description
     //             extra_info: empty_string(), // extra info
@@ -952,6 +2500,7 @@ mod market_basic_tests {
         );
 
         let market_id = contract.create_market(
+            &alice(),
             &CreateMarketArgs {
                 description: empty_string(), // market description
                 extra_info: empty_string(), // extra info
@@ -964,7 +2513,12 @@ mod market_basic_tests {
                 collateral_token_id: token(), // collateral_token_id
                 swap_fee: (10_u128.pow(24) / 50).into(), // swap fee, 2%
                 challenge_period: U64(1),
-                is_scalar: false // is_scalar
+                is_scalar: false, // is_scalar,
+                pricing_model: None, // pricing_model
+                fee_schedule: None, // fee_schedule
+                creator_fee: None, // creator_fee
+                parent: None, // parent
+                scalar_payout: None // scalar_payout
             }
         );
 
@@ -2,6 +2,8 @@ use crate::*;
 use near_sdk::{ PromiseResult, serde_json };
 use near_sdk::serde::{ Serialize, Deserialize };
 use crate::oracle::{ DataRequestArgs, DataRequestDataType };
+use crate::pool::FeeSchedule;
+use crate::market::ParentMarket;
 
 #[ext_contract(ext_self)]
 trait ProtocolResolver {
@@ -40,12 +42,6 @@ impl AMMContract {
         assert_eq!(oracle_config.bond_token, bond_token, "ERR_INVALID_BOND_TOKEN");
         assert!(validity_bond <= bond_in, "ERR_NOT_ENOUGH_BOND");
 
-        let outcomes: Option<Vec<String>> = if market_args.is_scalar {
-            None
-        } else {
-            Some(market_args.outcome_tags.clone())
-        };
-
         let data_type: DataRequestDataType = if market_args.is_scalar {
             DataRequestDataType::Number
         } else {
@@ -55,7 +51,9 @@ impl AMMContract {
         let remaining_bond: u128 = bond_in - validity_bond;
         let create_promise = self.create_data_request(&bond_token, validity_bond, DataRequestArgs {
             description: format!("{} - {}", market_args.description, market_args.extra_info),
-            outcomes,
+            // Categorical answers can be a single outcome tag, a JSON list of tied tags or a JSON object of weighted tags
+            // so the oracle can't restrict them to `outcome_tags`, they're validated in `set_outcome` instead
+            outcomes: None,
             settlement_time: ms_to_ns(market_args.resolution_time.into()),
             tags: vec![market_id.0.to_string()],
             sources: market_args.sources,
@@ -91,6 +89,7 @@ impl AMMContract {
     /**
      * @notice allows users to create new markets, can only be called internally
      * This function assumes the market data has been validated beforehand (ft_create_market_callback)
+     * @param creator is the account that creates the market and receives the creator fees
      * @param description is a detailed description of the market
      * @param extra_info extra information on how the market should be resoluted
      * @param outcomes the number of possible outcomes for the market
@@ -100,10 +99,15 @@ impl AMMContract {
      * @param resolution_time when the market can be resolved
      * @param collateral_token_id the `account_id` of the whitelisted token that is used as collateral for trading
      * @param swap_fee the fee that's taken from every swap and paid out to LPs
-     * @param is_scalar if the market is a scalar market (range)
+     * @param is_scalar if the market is a scalar market (range), its outcome tags are the ascending values of the outcomes
+     * @param pricing_model the market maker curve the pool prices outcome tokens with, defaults to CPMM
+     * @param fee_schedule optional ramp that raises the swap fee towards `end_time`
+     * @param creator_fee optional share of the swap fees that goes to the creator, in basis points
+     * @param parent optional outcome of another market this market is conditional on
+     * @param scalar_payout how a scalar market pays out between its outcome values, defaults to linear
      * @returns wrapped `market_id` 
      */
-    pub fn create_market(&mut self, payload: &CreateMarketArgs) -> U64 {
+    pub fn create_market(&mut self, creator: &AccountId, payload: &CreateMarketArgs) -> U64 {
         self.assert_unpaused();
         let swap_fee: u128 = payload.swap_fee.into();
         let market_id = self.markets.len();
@@ -115,18 +119,53 @@ impl AMMContract {
         assert!(payload.outcome_tags.len() as u16 == payload.outcomes, "ERR_INVALID_TAG_LENGTH");
         assert!(end_time > ns_to_ms(env::block_timestamp()), "ERR_INVALID_END_TIME");
         assert!(resolution_time >= end_time, "ERR_INVALID_RESOLUTION_TIME");
+        if payload.is_scalar {
+            // Scalar outcome tags are the values of the outcomes and need to be in strictly ascending order
+            let bounds: Vec<math::FixedDecimal> = payload.outcome_tags
+                .iter()
+                .map(|tag| math::FixedDecimal::parse(tag, constants::SCALAR_DECIMALS))
+                .collect();
+            assert!(bounds.windows(2).all(|pair| pair[0] < pair[1]), "ERR_INVALID_SCALAR_BOUNDS");
+        }
+
+        let parent = payload.parent.as_ref().map(|parent| {
+            let parent_market_id: u64 = parent.market_id.into();
+            let parent_market = self.markets.get(parent_market_id).expect("ERR_NO_PARENT_MARKET");
+            assert!(!parent_market.is_scalar, "ERR_SCALAR_PARENT_MARKET");
+            assert!(!parent_market.finalized, "ERR_PARENT_MARKET_FINALIZED");
+            assert!(parent.outcome < parent_market.pool.outcomes, "ERR_INVALID_PARENT_OUTCOME");
+            ParentMarket {
+                market_id: parent_market_id,
+                outcome: parent.outcome
+            }
+        });
+
+        let fee_schedule = payload.fee_schedule.as_ref().map(|schedule| {
+            let ramp_duration: u64 = schedule.ramp_duration.into();
+            FeeSchedule {
+                max_fee: schedule.max_fee.into(),
+                ramp_start: end_time.saturating_sub(ramp_duration),
+                ramp_end: end_time
+            }
+        });
 
         let pool = pool_factory::new_pool(
             market_id,
             payload.outcomes,
             payload.collateral_token_id.to_string(),
             token_decimals.unwrap(),
-            swap_fee
+            swap_fee,
+            payload.pricing_model.unwrap_or_default(),
+            fee_schedule,
+            payload.creator_fee.unwrap_or(0)
         );
 
         logger::log_pool(&pool);
 
         let market = Market {
+            description: payload.description.to_string(),
+            extra_info: payload.extra_info.to_string(),
+            categories: payload.categories.clone(),
             end_time: payload.end_time.into(),
             resolution_time: payload.resolution_time.into(),
             pool,
@@ -134,14 +173,48 @@ impl AMMContract {
             finalized: false,
             // Disable this market until the oracle request has been made
             enabled: false,
+            cancelled: false,
             is_scalar: payload.is_scalar,
+            scalar_payout: payload.scalar_payout.unwrap_or_default(),
             outcome_tags: payload.outcome_tags.clone(),
+            creator: creator.to_string(),
+            parent,
+            awaiting_parent: false,
         };
 
-        logger::log_create_market(&market, &payload.description, &payload.extra_info, &payload.categories);
+        logger::log_create_market(&market);
         logger::log_market_status(&market);
 
         self.markets.push(&market);
+
+        let mut creator_markets = self.markets_by_creator
+            .get(creator)
+            .unwrap_or_else(|| Vector::new(nested_storage_prefix(constants::CREATOR_MARKETS_TAG, creator.as_bytes())));
+        creator_markets.push(&market_id);
+        self.markets_by_creator.insert(creator, &creator_markets);
+
+        for (i, category) in market.categories.iter().enumerate() {
+            // A category that's listed more than once is only indexed once
+            if market.categories[..i].contains(category) {
+                continue;
+            }
+            let mut category_markets = self.markets_by_category
+                .get(category)
+                .unwrap_or_else(|| Vector::new(nested_storage_prefix(constants::CATEGORY_MARKETS_TAG, category.as_bytes())));
+            category_markets.push(&market_id);
+            self.markets_by_category.insert(category, &category_markets);
+        }
+        self.unfinalized_markets.insert(&market_id);
+
+        if let Some(parent) = &market.parent {
+            let mut child_markets = self.child_markets
+                .get(&parent.market_id)
+                .unwrap_or_else(|| Vector::new(nested_storage_prefix(constants::PARENT_CHILD_MARKETS_TAG, &parent.market_id.to_le_bytes())));
+            assert!(child_markets.len() < constants::MAX_CHILD_MARKETS, "ERR_MAX_CHILD_MARKETS");
+            child_markets.push(&market_id);
+            self.child_markets.insert(&parent.market_id, &child_markets);
+        }
+
         market_id.into()
     }
 
@@ -152,7 +225,7 @@ impl AMMContract {
         payload: CreateMarketArgs
     ) -> Promise {
         self.assert_unpaused();
-        let market_id = self.create_market(&payload);
+        let market_id = self.create_market(sender, &payload);
         oracle::fetch_oracle_config(&self.oracle)
             .then(
                 ext_self::proceed_datarequest_creation(
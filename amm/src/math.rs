@@ -5,6 +5,10 @@ construct_uint! {
     /// 256-bit unsigned integer.
     pub struct u256(4);
 }
+construct_uint! {
+    /// 512-bit unsigned integer.
+    pub struct u512(8);
+}
 
 
 /** 
@@ -61,4 +65,281 @@ pub fn simple_div_u128(base: u128, a: u128, b: u128) -> u128 {
     let base_u256 = u256::from(base);
 
     (a_u256 * base_u256 / b_u256).as_u128()
-}
\ No newline at end of file
+}
+
+pub const FIXED_ONE: u128 = 1_000_000_000_000_000_000; // fixed-point precision used for `exp_neg` and `ln`, 1e18 represents 1.0
+const LN_2: u128 = 693_147_180_559_945_309; // ln(2) in fixed-point
+
+/**
+ * @notice calculates e^-x using a Taylor series after reducing x by multiples of ln(2)
+ * @param x is a fixed-point number >= 0
+ * @returns e^-x as a fixed-point number
+ */
+pub fn exp_neg(x: u128) -> u128 {
+    // e^-x = 2^-k * e^-r where x = k * ln(2) + r and 0 <= r < ln(2)
+    let k = x / LN_2;
+    if k >= 64 {
+        // Smaller than the fixed-point precision
+        return 0;
+    }
+    let r = x - k * LN_2;
+
+    // e^-r = 1 - r + r^2/2! - r^3/3! + ...
+    let mut term = FIXED_ONE;
+    let mut sum_pos = FIXED_ONE;
+    let mut sum_neg = 0;
+    let mut n = 1;
+    while term > 0 {
+        term = term * r / FIXED_ONE / n;
+        if n % 2 == 1 {
+            sum_neg += term;
+        } else {
+            sum_pos += term;
+        }
+        n += 1;
+    }
+
+    (sum_pos - sum_neg) >> k
+}
+
+/**
+ * @notice calculates the natural logarithm of x using the series of 2 * atanh after normalizing x to [1, 2)
+ * @param x is a fixed-point number > 0
+ * @returns ln(x) as a signed fixed-point number
+ */
+pub fn ln(x: u128) -> i128 {
+    assert!(x > 0, "ERR_LN_OF_ZERO");
+
+    // x = m * 2^k where 1 <= m < 2
+    let mut m = x;
+    let mut k: i128 = 0;
+    while m >= 2 * FIXED_ONE {
+        m /= 2;
+        k += 1;
+    }
+    while m < FIXED_ONE {
+        m *= 2;
+        k -= 1;
+    }
+
+    // ln(m) = 2 * (z + z^3/3 + z^5/5 + ...) where z = (m - 1) / (m + 1) <= 1/3
+    let z = (m - FIXED_ONE) * FIXED_ONE / (m + FIXED_ONE);
+    let z_squared = z * z / FIXED_ONE;
+    let mut term = z;
+    let mut sum = 0;
+    let mut n = 1;
+    while term > 0 {
+        sum += term / n;
+        term = term * z_squared / FIXED_ONE;
+        n += 2;
+    }
+
+    k * LN_2 as i128 + 2 * sum as i128
+}
+
+/**
+ * @notice a signed decimal number stored as a fixed-point magnitude, used to resolve scalar markets without floating point errors
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedDecimal {
+    pub negative: bool, // true if the number is below zero, never true for zero
+    pub magnitude: u256, // absolute value of the number scaled by 10^decimals
+}
+
+impl FixedDecimal {
+    /**
+     * @notice parses a decimal string such as "-12.345" into a fixed-point number, digits beyond `decimals` are truncated
+     * @param value is the decimal string, an optional sign followed by digits with at most one decimal point
+     * @param decimals is the amount of decimals the fixed-point number keeps
+     * @returns the parsed `FixedDecimal`
+     */
+    pub fn parse(value: &str, decimals: u32) -> Self {
+        let (negative, unsigned) = if let Some(stripped) = value.strip_prefix('-') {
+            (true, stripped)
+        } else {
+            (false, value.strip_prefix('+').unwrap_or(value))
+        };
+
+        let mut parts = unsigned.splitn(2, '.');
+        let integer_part = parts.next().unwrap_or("");
+        let fraction_part = parts.next().unwrap_or("");
+        assert!(!integer_part.is_empty() || !fraction_part.is_empty(), "ERR_INVALID_DECIMAL");
+        assert!(integer_part.bytes().chain(fraction_part.bytes()).all(|byte| byte.is_ascii_digit()), "ERR_INVALID_DECIMAL");
+
+        let ten = u256::from(10);
+        let mut magnitude = u256::zero();
+        let fraction_digits = fraction_part.bytes().take(decimals as usize).collect::<Vec<u8>>();
+        for byte in integer_part.bytes().chain(fraction_digits.iter().copied()) {
+            magnitude = magnitude
+                .checked_mul(ten)
+                .and_then(|shifted| shifted.checked_add(u256::from(byte - b'0')))
+                .expect("ERR_DECIMAL_OVERFLOW");
+        }
+
+        // Scale the number up for the decimals that weren't provided
+        for _ in fraction_digits.len()..decimals as usize {
+            magnitude = magnitude.checked_mul(ten).expect("ERR_DECIMAL_OVERFLOW");
+        }
+
+        Self {
+            negative: negative && !magnitude.is_zero(),
+            magnitude
+        }
+    }
+
+    /**
+     * @returns the absolute difference between `self` and `other`
+     */
+    pub fn abs_diff(&self, other: &Self) -> u256 {
+        if self.negative == other.negative {
+            if self.magnitude >= other.magnitude { self.magnitude - other.magnitude } else { other.magnitude - self.magnitude }
+        } else {
+            self.magnitude.checked_add(other.magnitude).expect("ERR_DECIMAL_OVERFLOW")
+        }
+    }
+}
+
+impl Ord for FixedDecimal {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+            (false, true) => std::cmp::Ordering::Greater,
+            (true, false) => std::cmp::Ordering::Less,
+        }
+    }
+}
+
+impl PartialOrd for FixedDecimal {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/**
+ * @notice calculates the payout of the short (lower bound) outcome of a scalar market, answers outside of the bounds are clamped
+ *         payout = (upper_bound - answer) / (upper_bound - lower_bound) * denomination, rounded to the nearest unit
+ * @param lower_bound is the lower bound of the scalar market
+ * @param upper_bound is the upper bound of the scalar market, has to be larger than `lower_bound`
+ * @param answer is the resolved value
+ * @param denomination is the denomination the payout is returned in, e.g. the collateral denomination
+ * @returns the payout of the short outcome, the long outcome receives `denomination` minus this
+ */
+pub fn scalar_payout_short(lower_bound: &FixedDecimal, upper_bound: &FixedDecimal, answer: &FixedDecimal, denomination: u128) -> u128 {
+    assert!(upper_bound > lower_bound, "ERR_INVALID_SCALAR_BOUNDS");
+    let answer = std::cmp::min(std::cmp::max(answer, lower_bound), upper_bound);
+
+    let range = upper_bound.abs_diff(lower_bound);
+    let distance_to_upper = upper_bound.abs_diff(answer);
+
+    // distance_to_upper <= range so the result always fits within `denomination`, the product is computed in 512 bits since it can exceed 256 bits for large bounds
+    let range = to_u512(range);
+    let scaled = to_u512(distance_to_upper) * u512::from(denomination);
+    ((scaled + range / 2) / range).as_u128()
+}
+
+fn to_u512(value: u256) -> u512 {
+    let mut bytes = [0u8; 32];
+    value.to_little_endian(&mut bytes);
+    u512::from_little_endian(&bytes)
+}
+
+/**
+ * @param bounds are the ascending values of the outcomes
+ * @param answer is the resolved value
+ * @returns the index of the last outcome whose value is lower than or equal to `answer`, 0 if `answer` is below all values
+ */
+fn scalar_outcome_index(bounds: &[FixedDecimal], answer: &FixedDecimal) -> usize {
+    bounds.iter().rposition(|bound| bound <= answer).unwrap_or(0)
+}
+
+/**
+ * @notice splits the payout linearly between the two outcomes whose values surround `answer`, answers outside of the bounds are clamped
+ *         with two outcomes this is the classic long/short payout
+ * @param bounds are the ascending values of the outcomes, at least two
+ * @param answer is the resolved value
+ * @param denomination is the denomination the payout is returned in
+ * @returns the payout numerator indexed by outcome
+ */
+pub fn scalar_payout_linear(bounds: &[FixedDecimal], answer: &FixedDecimal, denomination: u128) -> Vec<u128> {
+    let index = std::cmp::min(scalar_outcome_index(bounds, answer), bounds.len() - 2);
+    let payout_lower = scalar_payout_short(&bounds[index], &bounds[index + 1], answer, denomination);
+
+    let mut payout_numerator = vec![0; bounds.len()];
+    payout_numerator[index] = payout_lower;
+    payout_numerator[index + 1] = denomination - payout_lower;
+    payout_numerator
+}
+
+/**
+ * @notice pays out the full `denomination` to the bucket containing `answer`, every outcome is a bucket that starts at its value
+ *         and ends at the value of the next outcome. Answers below the first value fall in the first bucket
+ * @param bounds are the ascending values of the outcomes
+ * @param answer is the resolved value
+ * @param denomination is the denomination the payout is returned in
+ * @returns the payout numerator indexed by outcome
+ */
+pub fn scalar_payout_bucket(bounds: &[FixedDecimal], answer: &FixedDecimal, denomination: u128) -> Vec<u128> {
+    let mut payout_numerator = vec![0; bounds.len()];
+    payout_numerator[scalar_outcome_index(bounds, answer)] = denomination;
+    payout_numerator
+}
+
+/**
+ * @notice splits `denomination` pro-rata to `weights`, the rounding remainder goes to the first outcome with a weight so the payout adds up exactly
+ * @param weights are the weights indexed by outcome, at least one has to be larger than 0
+ * @param denomination is the denomination the payout is returned in
+ * @returns the payout numerator indexed by outcome
+ */
+pub fn weighted_payout(weights: &[u128], denomination: u128) -> Vec<u128> {
+    let total_weight: u128 = weights.iter().sum();
+    assert!(total_weight > 0, "ERR_INVALID_WEIGHT");
+
+    let mut payout_numerator: Vec<u128> = weights.iter().map(|weight| simple_mul_u128(total_weight, denomination, *weight)).collect();
+    let remainder = denomination - payout_numerator.iter().sum::<u128>();
+    let first_winner = weights.iter().position(|weight| *weight > 0).unwrap();
+    payout_numerator[first_winner] += remainder;
+    payout_numerator
+}
+
+#[cfg(test)]
+mod math_tests {
+    use super::*;
+
+    #[test]
+    fn parse_fixed_decimal() {
+        assert_eq!(FixedDecimal::parse("12.5", 2), FixedDecimal { negative: false, magnitude: u256::from(1250) });
+        assert_eq!(FixedDecimal::parse("-0.05", 2), FixedDecimal { negative: true, magnitude: u256::from(5) });
+        assert_eq!(FixedDecimal::parse("-0", 2), FixedDecimal { negative: false, magnitude: u256::zero() });
+        assert_eq!(FixedDecimal::parse(".999", 2), FixedDecimal { negative: false, magnitude: u256::from(99) });
+        assert!(FixedDecimal::parse("-1", 2) < FixedDecimal::parse("-0.5", 2));
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INVALID_DECIMAL")]
+    fn parse_fixed_decimal_rejects_exponent() {
+        FixedDecimal::parse("1e5", 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_DECIMAL_OVERFLOW")]
+    fn parse_fixed_decimal_rejects_overflow() {
+        FixedDecimal::parse(&"9".repeat(60), 24);
+    }
+
+    #[test]
+    fn scalar_payout_near_u256_limit() {
+        let one = 10_u128.pow(24);
+        let lower_bound = FixedDecimal::parse("0", 24);
+        let upper_bound = FixedDecimal::parse(&format!("1{}", "0".repeat(53)), 24);
+        let answer = FixedDecimal::parse(&format!("25{}", "0".repeat(51)), 24);
+        assert_eq!(scalar_payout_short(&lower_bound, &upper_bound, &answer, one), one * 3 / 4);
+
+        // The full range between the most negative and most positive bound still fits in 256 bits
+        let max_bound = format!("1{}", "0".repeat(52));
+        let lower_bound = FixedDecimal::parse(&format!("-{}", max_bound), 24);
+        let upper_bound = FixedDecimal::parse(&max_bound, 24);
+        let answer = FixedDecimal::parse("0.000000000000000000000001", 24);
+        assert_eq!(scalar_payout_short(&lower_bound, &upper_bound, &answer, one), one / 2);
+    }
+}
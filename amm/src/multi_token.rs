@@ -0,0 +1,316 @@
+use crate::*;
+use near_sdk::PromiseResult;
+
+/*** NEP-245 multi token implementation over every outcome token and LP token of every pool ***/
+
+pub type TokenId = String; // "{market_id}:{outcome}" for outcome tokens and "{market_id}:lp" for LP tokens
+
+const GAS_FOR_MT_RESOLVE_TRANSFER: Gas = 20_000_000_000_000;
+const GAS_FOR_MT_TRANSFER_CALL: Gas = 25_000_000_000_000 + GAS_FOR_MT_RESOLVE_TRANSFER;
+
+pub enum MultiToken {
+    Outcome(U64, u16), // (market_id, outcome)
+    Lp(U64), // market_id
+}
+
+impl MultiToken {
+    /**
+     * @notice parses a NEP-245 token id into the token it references
+     * @param token_id is either "{market_id}:{outcome}" or "{market_id}:lp"
+     * @returns the referenced `MultiToken`
+     */
+    pub fn from_token_id(token_id: &str) -> Self {
+        let mut parts = token_id.splitn(2, ':');
+        let market_id: u64 = parts.next().unwrap().parse().expect("ERR_INVALID_TOKEN_ID");
+
+        match parts.next().expect("ERR_INVALID_TOKEN_ID") {
+            "lp" => MultiToken::Lp(U64(market_id)),
+            outcome => MultiToken::Outcome(U64(market_id), outcome.parse().expect("ERR_INVALID_TOKEN_ID"))
+        }
+    }
+}
+
+/**
+ * @returns the NEP-245 token id of an outcome token
+ */
+pub fn outcome_token_id(market_id: u64, outcome: u16) -> TokenId {
+    format!("{}:{}", market_id, outcome)
+}
+
+/**
+ * @returns the NEP-245 token id of a pool's LP token
+ */
+pub fn lp_token_id(market_id: u64) -> TokenId {
+    format!("{}:lp", market_id)
+}
+
+#[ext_contract(ext_mt_receiver)]
+pub trait MultiTokenReceiver {
+    // @returns amounts of unused tokens
+    fn mt_on_transfer(&mut self, sender_id: AccountId, previous_owner_ids: Vec<AccountId>, token_ids: Vec<TokenId>, amounts: Vec<U128>, msg: String) -> PromiseOrValue<Vec<U128>>;
+}
+
+#[ext_contract(ext_mt_resolver)]
+trait MultiTokenResolver {
+    fn mt_resolve_transfer(&mut self, previous_owner_ids: Vec<AccountId>, receiver_id: AccountId, token_ids: Vec<TokenId>, amounts: Vec<U128>) -> Vec<U128>;
+}
+
+#[near_bindgen]
+impl AMMContract {
+    /**
+     * @param account_id is the `AccountId` to retrieve the balance for
+     * @param token_id is the NEP-245 token id of the outcome or LP token
+     * @returns wrapped balance of `account_id`
+     */
+    pub fn mt_balance_of(
+        &self,
+        account_id: ValidAccountId,
+        token_id: TokenId
+    ) -> WrappedBalance {
+        U128(self.internal_mt_balance_of(account_id.as_ref(), &token_id))
+    }
+
+    /**
+     * @param account_id is the `AccountId` to retrieve the balances for
+     * @param token_ids is a list of NEP-245 token ids of outcome or LP tokens
+     * @returns wrapped balances of `account_id` in the same order as `token_ids`
+     */
+    pub fn mt_batch_balance_of(
+        &self,
+        account_id: ValidAccountId,
+        token_ids: Vec<TokenId>
+    ) -> Vec<WrappedBalance> {
+        token_ids.iter().map(|token_id| U128(self.internal_mt_balance_of(account_id.as_ref(), token_id))).collect()
+    }
+
+    /**
+     * @param token_id is the NEP-245 token id of the outcome or LP token
+     * @returns the wrapped total supply of the token, `None` if the market or outcome doesn't exist
+     */
+    pub fn mt_supply(
+        &self,
+        token_id: TokenId
+    ) -> Option<WrappedBalance> {
+        match MultiToken::from_token_id(&token_id) {
+            MultiToken::Outcome(market_id, outcome) => {
+                let market = self.markets.get(market_id.into())?;
+                if outcome >= market.pool.outcomes {
+                    return None;
+                }
+                Some(U128(market.pool.outcome_tokens.get(&outcome).map(|token| token.total_supply()).unwrap_or(0)))
+            },
+            MultiToken::Lp(market_id) => {
+                let market = self.markets.get(market_id.into())?;
+                Some(U128(market.pool.pool_token.total_supply()))
+            }
+        }
+    }
+
+    /**
+     * @notice transfers an outcome or LP token from the sender to `receiver_id`
+     * @param receiver_id is the account that should receive the tokens
+     * @param token_id is the NEP-245 token id of the outcome or LP token
+     * @param amount is the amount of tokens to transfer
+     * @param approval is not supported and should be `None`
+     * @param memo is an optional message for the receiver
+     */
+    #[payable]
+    pub fn mt_transfer(
+        &mut self,
+        receiver_id: ValidAccountId,
+        token_id: TokenId,
+        amount: WrappedBalance,
+        approval: Option<(AccountId, u64)>,
+        memo: Option<String>
+    ) {
+        self.assert_unpaused();
+        assert_one_yocto();
+        assert!(approval.is_none(), "ERR_APPROVALS_NOT_SUPPORTED");
+        let initial_storage = env::storage_usage();
+        let sender_id = env::predecessor_account_id();
+        let account = self.get_storage_account(&sender_id);
+
+        self.internal_mt_transfer(&sender_id, receiver_id.as_ref(), &token_id, amount.into(), memo, true);
+
+        self.use_storage(&sender_id, initial_storage, account.available);
+    }
+
+    /**
+     * @notice transfers a batch of outcome and/or LP tokens from the sender to `receiver_id`
+     * @param receiver_id is the account that should receive the tokens
+     * @param token_ids is a list of NEP-245 token ids of the outcome or LP tokens
+     * @param amounts is the amount of tokens to transfer for each token id
+     * @param approvals is not supported and should be `None`
+     * @param memo is an optional message for the receiver
+     */
+    #[payable]
+    pub fn mt_batch_transfer(
+        &mut self,
+        receiver_id: ValidAccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<WrappedBalance>,
+        approvals: Option<Vec<Option<(AccountId, u64)>>>,
+        memo: Option<String>
+    ) {
+        self.assert_unpaused();
+        assert_one_yocto();
+        assert!(approvals.is_none(), "ERR_APPROVALS_NOT_SUPPORTED");
+        assert_eq!(token_ids.len(), amounts.len(), "ERR_INVALID_AMOUNTS_LENGTH");
+        let initial_storage = env::storage_usage();
+        let sender_id = env::predecessor_account_id();
+        let account = self.get_storage_account(&sender_id);
+
+        for (token_id, amount) in token_ids.iter().zip(amounts.iter()) {
+            self.internal_mt_transfer(&sender_id, receiver_id.as_ref(), token_id, u128::from(*amount), memo.clone(), true);
+        }
+
+        self.use_storage(&sender_id, initial_storage, account.available);
+    }
+
+    /**
+     * @notice transfers an outcome or LP token from the sender to `receiver_id` and calls `mt_on_transfer` on the receiver
+     *         any tokens the receiver returns as unused are transferred back to the sender in `mt_resolve_transfer`
+     * @param receiver_id is the contract that should receive the tokens
+     * @param token_id is the NEP-245 token id of the outcome or LP token
+     * @param amount is the amount of tokens to transfer
+     * @param approval is not supported and should be `None`
+     * @param memo is an optional message for the receiver
+     * @param msg is passed on to the receiver
+     * @returns a promise that resolves to the amounts of tokens used by the receiver
+     */
+    #[payable]
+    pub fn mt_transfer_call(
+        &mut self,
+        receiver_id: ValidAccountId,
+        token_id: TokenId,
+        amount: WrappedBalance,
+        approval: Option<(AccountId, u64)>,
+        memo: Option<String>,
+        msg: String
+    ) -> Promise {
+        self.assert_unpaused();
+        assert_one_yocto();
+        assert!(approval.is_none(), "ERR_APPROVALS_NOT_SUPPORTED");
+        assert!(env::prepaid_gas() > GAS_FOR_MT_TRANSFER_CALL + GAS_FOR_FEES_TRANSFER, "ERR_MORE_GAS_REQUIRED");
+        let initial_storage = env::storage_usage();
+        let sender_id = env::predecessor_account_id();
+        let account = self.get_storage_account(&sender_id);
+        let receiver_id: AccountId = receiver_id.into();
+
+        self.internal_mt_transfer(&sender_id, &receiver_id, &token_id, amount.into(), memo, true);
+
+        self.use_storage(&sender_id, initial_storage, account.available);
+
+        ext_mt_receiver::mt_on_transfer(
+            sender_id.to_string(),
+            vec![sender_id.to_string()],
+            vec![token_id.to_string()],
+            vec![amount],
+            msg,
+            &receiver_id,
+            0,
+            env::prepaid_gas() - GAS_FOR_MT_TRANSFER_CALL - GAS_FOR_FEES_TRANSFER
+        ).then(ext_mt_resolver::mt_resolve_transfer(
+            vec![sender_id],
+            receiver_id.to_string(),
+            vec![token_id],
+            vec![amount],
+            &env::current_account_id(),
+            0,
+            GAS_FOR_MT_RESOLVE_TRANSFER
+        ))
+    }
+
+    /**
+     * @notice resolves an `mt_transfer_call` by returning the tokens the receiver didn't use to their previous owners
+     * @returns the amounts of tokens that were used by the receiver in the same order as `token_ids`
+     */
+    pub fn mt_resolve_transfer(
+        &mut self,
+        previous_owner_ids: Vec<AccountId>,
+        receiver_id: AccountId,
+        token_ids: Vec<TokenId>,
+        amounts: Vec<WrappedBalance>
+    ) -> Vec<WrappedBalance> {
+        assert_self();
+        let amounts: Vec<u128> = amounts.into_iter().map(u128::from).collect();
+
+        let unused_amounts: Vec<u128> = match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(value) => {
+                match serde_json::from_slice::<Vec<U128>>(&value) {
+                    Ok(unused_amounts) if unused_amounts.len() == amounts.len() => {
+                        unused_amounts.iter().zip(amounts.iter()).map(|(unused_amount, amount)| {
+                            std::cmp::min(*amount, u128::from(*unused_amount))
+                        }).collect()
+                    },
+                    _ => amounts.clone(),
+                }
+            },
+            PromiseResult::Failed => amounts.clone(),
+        };
+
+        token_ids.iter().enumerate().map(|(i, token_id)| {
+            let receiver_balance = self.internal_mt_balance_of(&receiver_id, token_id);
+            let refund_amount = std::cmp::min(unused_amounts[i], receiver_balance);
+
+            if refund_amount > 0 {
+                // Refunds have to go through even if the market was disabled while the receiver was called
+                self.internal_mt_transfer(&receiver_id, &previous_owner_ids[i], token_id, refund_amount, None, false);
+            }
+
+            U128(amounts[i] - refund_amount)
+        }).collect()
+    }
+}
+
+impl AMMContract {
+    /**
+     * @param account_id is the `AccountId` to retrieve the balance for
+     * @param token_id is the NEP-245 token id of the outcome or LP token
+     * @returns the balance of `account_id`, 0 if the outcome token hasn't been minted yet
+     */
+    fn internal_mt_balance_of(
+        &self,
+        account_id: &AccountId,
+        token_id: &str
+    ) -> Balance {
+        match MultiToken::from_token_id(token_id) {
+            MultiToken::Outcome(market_id, outcome) => {
+                let market = self.get_market_expect(market_id);
+                market.pool.outcome_tokens.get(&outcome).map(|token| token.get_balance(account_id)).unwrap_or(0)
+            },
+            MultiToken::Lp(market_id) => {
+                let market = self.get_market_expect(market_id);
+                market.pool.get_pool_token_balance(account_id)
+            }
+        }
+    }
+
+    /**
+     * @notice transfers an outcome or LP token between two accounts, fees accrued on LP tokens are paid out to `sender_id`
+     * @param sender_id is the account that's sending the tokens
+     * @param receiver_id is the account that should receive the tokens
+     * @param token_id is the NEP-245 token id of the outcome or LP token
+     * @param amount is the amount of tokens to transfer
+     * @param memo is an optional message that's logged with the transfer
+     * @param assert_enabled is whether the transfer should fail if the market is disabled, `false` for refunds of a transfer call
+     */
+    fn internal_mt_transfer(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        token_id: &str,
+        amount: u128,
+        memo: Option<String>,
+        assert_enabled: bool
+    ) {
+        match MultiToken::from_token_id(token_id) {
+            MultiToken::Outcome(market_id, outcome) => self.internal_outcome_transfer(market_id, outcome, sender_id, receiver_id, amount, memo, assert_enabled),
+            MultiToken::Lp(market_id) => {
+                // The fee transfer promise is scheduled on its own and doesn't influence the token transfer, failed fee transfers are re-credited
+                self.internal_lp_transfer(market_id, sender_id, receiver_id, amount, memo, assert_enabled);
+            }
+        }
+    }
+}
@@ -2,8 +2,55 @@ use std::cmp::Ordering;
 use crate::*;
 use crate::resolution_escrow::ResolutionEscrows;
 use crate::outcome_token::MintableFungibleToken;
+use crate::pricing_model::{PricingModel, PricingModelType, Cpmm, Lmsr};
+use crate::reward_program::RewardProgram;
+use crate::price_oracle::PriceOracle;
+use crate::market::AccountPosition;
 use near_sdk::Balance;
 
+/**
+ * @notice a swap fee that ramps up linearly from the pool's base `swap_fee` to `max_fee` towards the end of the market
+ */
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct FeeSchedule {
+    pub max_fee: Balance, // the fee that's in effect from `ramp_end` onwards, denominated in the collateral denomination
+    pub ramp_start: Timestamp, // time in ms from which the fee starts to increase
+    pub ramp_end: Timestamp, // time in ms at which the fee reaches `max_fee`, the market's end time
+}
+
+impl FeeSchedule {
+    /**
+     * @param base_fee is the fee that's in effect before `ramp_start`
+     * @param timestamp is the time in ms to get the fee for
+     * @returns the swap fee that's in effect at `timestamp`
+     */
+    pub fn fee_at(&self, base_fee: Balance, timestamp: Timestamp) -> Balance {
+        if timestamp <= self.ramp_start {
+            base_fee
+        } else if timestamp >= self.ramp_end {
+            self.max_fee
+        } else {
+            let elapsed = u128::from(timestamp - self.ramp_start);
+            let ramp_duration = u128::from(self.ramp_end - self.ramp_start);
+            base_fee + (self.max_fee - base_fee) * elapsed / ramp_duration
+        }
+    }
+}
+
+/**
+ * @notice the result of a trade simulated against the current pool balances, prices are denominated in the collateral denomination
+ */
+#[derive(Serialize, Deserialize)]
+pub struct Quote {
+    pub collateral: WrappedBalance, // collateral in for a buy, fees included, or collateral out for a sell, after the fee is deducted
+    pub shares: WrappedBalance, // shares out for a buy or shares in for a sell
+    pub fee: WrappedBalance, // swap fee paid on the trade
+    pub average_price: WrappedBalance, // `collateral` per share, the price a buyer pays including the fee or a seller receives after the fee
+    pub price_impact: WrappedBalance, // relative difference between the execution price without fees and `spot_price_before`
+    pub spot_price_before: WrappedBalance, // spot price of the traded outcome without fees before the trade
+    pub spot_prices_after: Vec<WrappedBalance>, // spot prices of every outcome without fees after the trade, indexed by outcome
+}
+
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct Pool {
     pub id: u64, // unique identifier - used for storage pointers
@@ -15,8 +62,17 @@ pub struct Pool {
     pub swap_fee: Balance, // the fee paid to LPs on every swap, denominated in 1e4, meaning that 1 = 0.01% and 10000 = 100%
     pub withdrawn_fees: LookupMap<AccountId, Balance>, // amount of accumulated fees an account is (no longer) ineligable to claim
     pub total_withdrawn_fees: Balance, // total withdrawn fees
+    pub unpaid_fees: LookupMap<AccountId, Balance>, // fees that were withdrawn but failed to transfer, claimable again by the account
     pub fee_pool_weight: u128, // weighted fee pool used to calculate fees owed to accounts based on LP token share
-    pub resolution_escrow: ResolutionEscrows // maps account_id => Resolution Escrow scruct
+    pub resolution_escrow: ResolutionEscrows, // maps account_id => Resolution Escrow scruct
+    pub pricing_model: PricingModelType, // the market maker curve used to price outcome tokens
+    pub lmsr_liquidity: u128, // the LMSR liquidity parameter, scales with the pool balances and is 0 for CPMM pools
+    pub fee_schedule: Option<FeeSchedule>, // optional schedule that increases the swap fee towards the end of the market
+    pub protocol_fees: Balance, // protocol's share of swap fees that hasn't been moved to the contract's protocol balance yet
+    pub creator_fee_share: u16, // share of the swap fees remaining after the protocol fee that goes to the market creator, in basis points
+    pub creator_fees: Balance, // creator fees that haven't been claimed by the market creator yet
+    pub reward_program: Option<RewardProgram>, // optional liquidity mining program that streams a reward token to LPs
+    pub price_oracle: PriceOracle, // time-weighted price accumulators, updated whenever the pool's prices can change
 }
 
 impl Pool {
@@ -28,6 +84,9 @@ impl Pool {
      * @param collateral_decimals is the amount of decimals the corresponding collateral token has
      * @param outcomes is the number outcomes in the pool
      * @param swap_fee is the fee paid out to LPs on every swap (buy or sell) denominated in 1e4
+     * @param pricing_model is the market maker curve used to price outcome tokens
+     * @param fee_schedule is an optional schedule that ramps the swap fee up from `swap_fee` towards the end of the market
+     * @param creator_fee_share is the share of the swap fees, after the protocol fee, that goes to the market creator in basis points
      * @returns a new `Pool` instance
      */
     pub fn new(
@@ -35,12 +94,20 @@ impl Pool {
         collateral_token_id: AccountId,
         collateral_decimals: u32,
         outcomes: u16,
-        swap_fee: Balance
+        swap_fee: Balance,
+        pricing_model: PricingModelType,
+        fee_schedule: Option<FeeSchedule>,
+        creator_fee_share: u16
     ) -> Self {
         assert!(outcomes >= constants::MIN_OUTCOMES, "ERR_MIN_OUTCOMES");
         assert!(outcomes <= constants::MAX_OUTCOMES, "ERR_MAX_OUTCOMES");
         let collateral_denomination = 10_u128.pow(collateral_decimals);
         assert!(swap_fee == 0 || (swap_fee <= collateral_denomination / 20 && swap_fee >= collateral_denomination / 10_000), "ERR_INVALID_FEE");
+        if let Some(schedule) = &fee_schedule {
+            assert!(schedule.max_fee >= swap_fee && schedule.max_fee <= collateral_denomination / 20, "ERR_INVALID_FEE");
+            assert!(schedule.ramp_start < schedule.ramp_end, "ERR_INVALID_FEE_SCHEDULE");
+        }
+        assert!(creator_fee_share <= constants::MAX_CREATOR_FEE_SHARE, "ERR_INVALID_CREATOR_FEE");
 
         Self {
             id: pool_id,
@@ -52,16 +119,38 @@ impl Pool {
             swap_fee,
             withdrawn_fees: LookupMap::new(format!("p{}wf", pool_id).as_bytes().to_vec()),
             total_withdrawn_fees: 0,
+            unpaid_fees: LookupMap::new(format!("p{}uf", pool_id).as_bytes().to_vec()),
             fee_pool_weight: 0,
-            resolution_escrow: ResolutionEscrows::new(pool_id)
+            resolution_escrow: ResolutionEscrows::new(pool_id),
+            pricing_model,
+            lmsr_liquidity: 0,
+            fee_schedule,
+            protocol_fees: 0,
+            creator_fee_share,
+            creator_fees: 0,
+            reward_program: None,
+            price_oracle: PriceOracle::new(pool_id)
         }
     }
 
     /**
-     * @returns the pool's swap fee
+     * @returns the implementation of the pool's market maker curve
+     */
+    fn get_pricing_model(&self) -> Box<dyn PricingModel> {
+        match self.pricing_model {
+            PricingModelType::Cpmm => Box::new(Cpmm { collateral_denomination: self.collateral_denomination }),
+            PricingModelType::Lmsr => Box::new(Lmsr { collateral_denomination: self.collateral_denomination, liquidity: self.lmsr_liquidity })
+        }
+    }
+
+    /**
+     * @returns the pool's swap fee that's in effect at the current block timestamp
      */
     pub fn get_swap_fee(&self) -> Balance {
-        self.swap_fee
+        match &self.fee_schedule {
+            Some(schedule) => schedule.fee_at(self.swap_fee, ns_to_ms(env::block_timestamp())),
+            None => self.swap_fee
+        }
     }
 
     /**
@@ -89,6 +178,26 @@ impl Pool {
         self.pool_token.get_balance(account_id)
     }
 
+    /**
+     * @param account_id is the account to return the position of
+     * @returns the outcome token and pool token balances, fees and escrow of `account_id` in this pool
+     */
+    pub fn get_account_position(&self, account_id: &AccountId) -> AccountPosition {
+        let pool_token_balance = self.get_pool_token_balance(account_id);
+        let escrow_account = self.resolution_escrow.get_or_new(account_id.to_string());
+        let outcomes = 0..self.outcomes;
+
+        AccountPosition {
+            market_id: U64(self.id),
+            outcome_balances: outcomes.clone().map(|outcome| U128(self.get_share_balance(account_id, outcome))).collect(),
+            pool_token_balance: U128(pool_token_balance),
+            fees_withdrawable: U128(if pool_token_balance > 0 { self.get_fees_withdrawable(account_id) } else { 0 }),
+            spent: outcomes.map(|outcome| U128(escrow_account.get_spent(outcome))).collect(),
+            escrow_valid: U128(escrow_account.valid),
+            escrow_invalid: U128(escrow_account.invalid),
+        }
+    }
+
     pub fn get_pool_balances(&self) -> Vec<Balance> {
         self.outcome_tokens.iter().map(|(_outcome, token)| {
             token.get_balance(&env::current_account_id())
@@ -100,7 +209,7 @@ impl Pool {
         sender: &AccountId,
         total_in: Balance,
         weight_indication: Option<Vec<u128>>
-    ) {
+    ) -> Vec<Balance> {
         assert!(total_in >= self.min_liquidity_amount(), "ERR_MIN_LIQUIDITY_AMOUNT");
         let mut outcome_tokens_to_return: Vec<u128> = vec![];
 
@@ -110,9 +219,20 @@ impl Pool {
             assert!(weights.len() as u16 == self.outcomes, "ERR_INVALID_WEIGHTS");
             let max_weight = weights.iter().max().unwrap();
 
-            for (i, weight) in weights.iter().enumerate() {
-                let remaining = math::complex_div_u128(self.collateral_denomination, math::complex_mul_u128(self.collateral_denomination, total_in, *weight), *max_weight);
-                outcome_tokens_to_return.insert(i, total_in - remaining);
+            match self.pricing_model {
+                PricingModelType::Cpmm => {
+                    for (i, weight) in weights.iter().enumerate() {
+                        let remaining = math::complex_div_u128(self.collateral_denomination, math::complex_mul_u128(self.collateral_denomination, total_in, *weight), *max_weight);
+                        outcome_tokens_to_return.insert(i, total_in - remaining);
+                    }
+                },
+                PricingModelType::Lmsr => {
+                    let (liquidity, balances) = Lmsr::initial_balances(total_in, &weights);
+                    self.lmsr_liquidity = liquidity;
+                    for (i, remaining) in balances.iter().enumerate() {
+                        outcome_tokens_to_return.insert(i, total_in - remaining);
+                    }
+                }
             }
 
             total_in
@@ -128,6 +248,11 @@ impl Pool {
                 outcome_tokens_to_return.insert(i, total_in - remaining);
             }
 
+            // Pool balances grow by the same ratio so the LMSR liquidity parameter has to grow with them to keep prices the same
+            if self.pricing_model == PricingModelType::Lmsr {
+                self.lmsr_liquidity += math::simple_mul_u128(*max_balance, self.lmsr_liquidity, total_in);
+            }
+
             math::complex_div_u128(self.collateral_denomination, math::complex_mul_u128(self.collateral_denomination, total_in, pool_supply), *max_balance)
         };
 
@@ -138,10 +263,91 @@ impl Pool {
         );
 
         self.mint_internal(sender, to_mint);
+        self.record_prices();
 
         logger::log_pool(&self);
         logger::log_transaction(&logger::TransactionType::AddLiquidity, &sender, total_in, to_mint, U64(self.id), None);
-        logger::log_user_pool_status(&self, &env::predecessor_account_id(), total_in);
+        logger::log_user_pool_status(&self, &sender, total_in);
+
+        outcome_tokens_to_return
+    }
+
+    /**
+     * @notice adds liquidity without leaving the sender with outcome tokens, the outcome tokens `add_liquidity` returns are sold back into the pool
+     *         and the collateral that frees up is added as liquidity again until less than the minimum liquidity amount is left
+     *         those sales move the prices away from the ones the liquidity was added at, panics if any spot price moves more than `MAX_ZAP_PRICE_DEVIATION`
+     * @param sender is the account that provides liquidity
+     * @param total_in is the amount of collateral to provide
+     * @param weight_indication is only required if the pool has no liquidity yet, see `add_liquidity`
+     * @param protocol_fee_share is the share of the swap fees on the sales that goes to the protocol, denominated in basis points
+     * @returns the collateral that wasn't added and the shares that couldn't be sold, indexed by outcome
+     */
+    pub fn add_liquidity_zap(
+        &mut self,
+        sender: &AccountId,
+        total_in: Balance,
+        weight_indication: Option<Vec<u128>>,
+        protocol_fee_share: u16
+    ) -> (Balance, Vec<Balance>) {
+        let mut weight_indication = weight_indication;
+        let mut outcome_dust: Vec<Balance> = vec![0; self.outcomes as usize];
+        let mut remaining = total_in;
+        let mut rounds = 0;
+        let mut initial_prices: Vec<Balance> = vec![];
+
+        loop {
+            let outcome_tokens_returned = self.add_liquidity(sender, remaining, weight_indication.take());
+            if rounds == 0 {
+                initial_prices = (0..self.outcomes).map(|outcome| self.get_spot_price_sans_fee(outcome)).collect();
+            }
+            remaining = 0;
+            rounds += 1;
+
+            for (i, shares) in outcome_tokens_returned.iter().enumerate() {
+                let (collateral_out, unsold) = self.sell_shares_internal(sender, *shares, i as u16, protocol_fee_share);
+                remaining += collateral_out;
+                outcome_dust[i] += unsold;
+            }
+
+            if remaining < self.min_liquidity_amount() || rounds == constants::MAX_ZAP_ROUNDS {
+                break;
+            }
+        }
+
+        let max_deviation = self.collateral_denomination / 100 * constants::MAX_ZAP_PRICE_DEVIATION;
+        for (outcome, initial_price) in initial_prices.iter().enumerate() {
+            let price = self.get_spot_price_sans_fee(outcome as u16);
+            let deviation = if price > *initial_price { price - initial_price } else { initial_price - price };
+            assert!(deviation <= max_deviation, "ERR_ZAP_PRICE_DEVIATION");
+        }
+
+        (remaining, outcome_dust)
+    }
+
+    /**
+     * @notice sells as much of `shares` back into the pool as possible
+     * @param sender is the account that sells its shares
+     * @param shares is the amount of shares to sell
+     * @param outcome is the outcome of the shares
+     * @param protocol_fee_share is the share of the swap fees that goes to the protocol, denominated in basis points
+     * @returns the collateral the sender gets out, excluding what's escrowed on the sale, and the amount of shares that couldn't be sold
+     */
+    fn sell_shares_internal(
+        &mut self,
+        sender: &AccountId,
+        shares: Balance,
+        outcome: u16,
+        protocol_fee_share: u16
+    ) -> (Balance, Balance) {
+        let collateral_out = if shares > 0 { self.calc_sell_shares_to_collateral(shares, outcome) } else { 0 };
+        if collateral_out == 0 {
+            return (0, shares);
+        }
+
+        // Whatever is escrowed on the sale stays claimable by the sender on resolution
+        let shares_in = self.calc_sell_collateral_out(collateral_out, outcome);
+        let escrowed = self.sell(sender, collateral_out, outcome, shares_in, protocol_fee_share);
+        (collateral_out - escrowed, shares - shares_in)
     }
 
     fn mint_and_transfer_outcome_tokens(
@@ -228,12 +434,54 @@ impl Pool {
             self.outcome_tokens.insert(&outcome, &token);
         }
 
+        // Pool balances shrink by the same ratio so the LMSR liquidity parameter has to shrink with them to keep prices the same
+        if self.pricing_model == PricingModelType::Lmsr {
+            self.lmsr_liquidity -= math::simple_mul_u128(pool_token_supply, self.lmsr_liquidity, total_in);
+        }
+
         self.resolution_escrow.insert(&sender, &escrow_account);
         let fees = self.burn_internal(sender, total_in);
+        self.record_prices();
         logger::log_exit_pool(&self, sender, total_in, fees);
         fees
     }
 
+    /**
+     * @notice exits the pool and turns the outcome tokens that come out into collateral, the shares all outcomes have in common are merged
+     *         and if `sell_remaining` is true the rest is sold back into the pool
+     * @param sender is the account that exits the pool
+     * @param total_in is the amount of pool tokens to redeem
+     * @param sell_remaining is whether the shares that can't be merged are sold
+     * @param protocol_fee_share is the share of the swap fees on the sales that goes to the protocol, denominated in basis points
+     * @returns the collateral to transfer to the sender, fees earned included
+     */
+    pub fn exit_pool_to_collateral(
+        &mut self,
+        sender: &AccountId,
+        total_in: Balance,
+        sell_remaining: bool,
+        protocol_fee_share: u16
+    ) -> Balance {
+        let balances_before: Vec<Balance> = (0..self.outcomes).map(|outcome| self.get_share_balance(sender, outcome)).collect();
+        let fees = self.exit_pool(sender, total_in);
+        let shares_out: Vec<Balance> = (0..self.outcomes).map(|outcome| self.get_share_balance(sender, outcome) - balances_before[outcome as usize]).collect();
+
+        let to_merge = *shares_out.iter().min().unwrap();
+        let mut collateral_out = fees;
+        if to_merge > 0 {
+            collateral_out += to_merge - self.merge_complete_set(sender, to_merge);
+        }
+
+        if sell_remaining {
+            for (i, shares) in shares_out.iter().enumerate() {
+                let (collateral_from_sale, _unsold) = self.sell_shares_internal(sender, shares - to_merge, i as u16, protocol_fee_share);
+                collateral_out += collateral_from_sale;
+            }
+        }
+
+        collateral_out
+    }
+
     pub fn burn_outcome_tokens_redeem_collateral(
         &mut self,
         sender: &AccountId,
@@ -290,6 +538,90 @@ impl Pool {
         in_escrow
     }
 
+    /**
+     * @notice mints `amount` shares of every outcome for `sender` in return for `amount` collateral without touching the pool balances
+     *         the collateral is accounted as spent evenly across the outcomes so the full amount is refundable if the market turns out invalid
+     * @param sender is the account to mint the complete set for
+     * @param amount is the amount of collateral put in and the amount of shares minted for each outcome
+     */
+    pub fn mint_complete_set(
+        &mut self,
+        sender: &AccountId,
+        amount: Balance
+    ) {
+        assert!(amount > 0, "ERR_ZERO_AMOUNT");
+        let mut escrow_account = self.resolution_escrow.get_or_new(sender.to_string());
+        let spent_per_outcome = amount / self.outcomes as u128;
+        // The division remainder is added to the first outcome so the total spent adds up to `amount`
+        let remainder = amount - spent_per_outcome * self.outcomes as u128;
+
+        for outcome in 0..self.outcomes {
+            let spent_on_outcome = if outcome == 0 { spent_per_outcome + remainder } else { spent_per_outcome };
+            let account_spent = escrow_account.add_to_spent(outcome, spent_on_outcome);
+            logger::log_account_outcome_spent(&self, sender, outcome, account_spent);
+
+            let mut outcome_token = self.outcome_tokens
+                .get(&outcome)
+                .unwrap_or_else(|| { MintableFungibleToken::new(self.id, outcome, 0) });
+            outcome_token.mint(sender, amount);
+            self.outcome_tokens.insert(&outcome, &outcome_token);
+        }
+
+        self.resolution_escrow.insert(sender, &escrow_account);
+    }
+
+    /**
+     * @notice burns `amount` shares of every outcome held by `sender` in return for `amount` collateral
+     *         the spent on the burned shares is compared to `amount`: any profit is escrowed until the market resolves valid
+     *         and any loss is escrowed so it can be reclaimed if the market resolves invalid
+     * @param sender is the account to merge the complete set for
+     * @param amount is the amount of shares of each outcome to burn
+     * @returns the amount of collateral that's escrowed and should not be transferred out
+     */
+    pub fn merge_complete_set(
+        &mut self,
+        sender: &AccountId,
+        amount: Balance
+    ) -> Balance {
+        assert!(amount > 0, "ERR_ZERO_AMOUNT");
+        let mut escrow_account = self.resolution_escrow.get_or_new(sender.to_string());
+        let mut spent_on_merged_shares = 0;
+
+        for outcome in 0..self.outcomes {
+            let mut outcome_token = self.outcome_tokens.get(&outcome).expect("ERR_NO_OUTCOME");
+            let outcome_balance = outcome_token.get_balance(sender);
+            assert!(outcome_balance >= amount, "sender doesn't have enough shares for outcome with index {}", outcome);
+
+            // The spent that's removed is relative to the share of the balance that's burned: spent * amount / balance
+            let spent_on_outcome = math::simple_mul_u128(outcome_balance, escrow_account.get_spent(outcome), amount);
+            let new_spent = escrow_account.sub_from_spent(outcome, spent_on_outcome);
+            logger::log_account_outcome_spent(&self, sender, outcome, new_spent);
+            spent_on_merged_shares += spent_on_outcome;
+
+            outcome_token.burn(sender, amount);
+            self.outcome_tokens.insert(&outcome, &outcome_token);
+        }
+
+        let in_escrow = match spent_on_merged_shares.cmp(&amount) {
+            Ordering::Less => {
+                let to_escrow = amount - spent_on_merged_shares;
+                let valid_escrow = escrow_account.add_to_escrow_valid(to_escrow);
+                logger::log_to_valid_escrow(self.id, sender, valid_escrow);
+                to_escrow
+            },
+            Ordering::Greater => {
+                let invalid_escrow = escrow_account.add_to_escrow_invalid(spent_on_merged_shares - amount);
+                logger::log_to_invalid_escrow(self.id, sender, invalid_escrow);
+                0
+            },
+            Ordering::Equal => 0
+        };
+
+        self.resolution_escrow.insert(sender, &escrow_account);
+
+        in_escrow
+    }
+
     // move to view impl
 
     fn get_and_clear_balances(
@@ -331,6 +663,14 @@ impl Pool {
             fees = self.withdraw_fees(account_id);
         }
 
+        // Rewards need to be settled against the balances and supply from before the transfer
+        if let Some(reward_program) = &mut self.reward_program {
+            reward_program.update(self.pool_token.total_supply(), ns_to_ms(env::block_timestamp()));
+            for account_id in from.iter().chain(to.iter()) {
+                reward_program.settle(account_id, self.pool_token.get_balance(account_id));
+            }
+        }
+
         let total_supply = self.pool_token.total_supply();
         let ineligible_fee_amount = match total_supply {
             0 => amount,
@@ -371,7 +711,7 @@ impl Pool {
         let pool_token_total_supply = self.pool_token.total_supply();
         let raw_amount = math::complex_div_u128(self.collateral_denomination, math::complex_mul_u128(self.collateral_denomination, self.fee_pool_weight, pool_token_bal), pool_token_total_supply);
         let ineligible_fee_amount = self.withdrawn_fees.get(account_id).unwrap_or(0);
-        raw_amount - ineligible_fee_amount
+        raw_amount - ineligible_fee_amount + self.unpaid_fees.get(account_id).unwrap_or(0)
     }
 
     pub fn withdraw_fees(
@@ -382,7 +722,8 @@ impl Pool {
         let pool_token_total_supply = self.pool_token.total_supply();
         let raw_amount = math::simple_mul_u128(pool_token_total_supply, self.fee_pool_weight, pool_token_bal);
         let withdrawn_fees = self.withdrawn_fees.get(account_id).unwrap_or(0);
-        let withdrawable_amount = raw_amount - withdrawn_fees;
+        let unpaid_fees = self.unpaid_fees.remove(account_id).unwrap_or(0);
+        let withdrawable_amount = raw_amount - withdrawn_fees + unpaid_fees;
         if withdrawable_amount > 0 {
             self.withdrawn_fees.insert(account_id, &raw_amount);
             self.total_withdrawn_fees += withdrawable_amount;
@@ -392,6 +733,22 @@ impl Pool {
         withdrawable_amount
     }
 
+    /**
+     * @notice re-credits fees that were withdrawn for `account_id` but failed to transfer so they can be claimed again
+     *         they're kept apart from `withdrawn_fees` because the account's pool token balance may have changed since the withdrawal
+     * @param account_id is the account the fees were withdrawn for
+     * @param amount is the amount of fees that failed to transfer
+     */
+    pub fn refund_fees(
+        &mut self,
+        account_id: &AccountId,
+        amount: Balance
+    ) {
+        let unpaid_fees = self.unpaid_fees.get(account_id).unwrap_or(0);
+        self.unpaid_fees.insert(account_id, &(unpaid_fees + amount));
+        self.total_withdrawn_fees -= amount;
+    }
+
     pub fn calc_buy_amount(
         &self,
         collateral_in: Balance,
@@ -399,24 +756,63 @@ impl Pool {
     ) -> Balance {
         assert!(outcome_target <= self.outcomes, "ERR_INVALID_OUTCOME");
 
-        let outcome_tokens = &self.outcome_tokens;
-        let collateral_in_minus_fees = collateral_in - math::complex_mul_u128(self.collateral_denomination, collateral_in, self.swap_fee);
-        let token_to_buy = outcome_tokens.get(&outcome_target).expect("ERR_NO_TOKEN");
-        let token_to_buy_balance = token_to_buy.get_balance(&env::current_account_id());
-        let mut new_buy_token_balance = token_to_buy_balance;
+        let balances = self.get_pool_balances();
+        assert!((outcome_target as usize) < balances.len(), "ERR_NO_TOKEN");
+        self.calc_buy_amount_from_balances(&balances, collateral_in, outcome_target)
+    }
 
-        for (outcome, token) in outcome_tokens.iter() {
-            if outcome != outcome_target {
-                let balance = token.get_balance(&env::current_account_id());
-                let dividend = math::complex_mul_u128(self.collateral_denomination, new_buy_token_balance, balance);
-                let divisor = balance + collateral_in_minus_fees;
+    /**
+     * @notice calculates the amount of collateral that needs to be put in to receive at least `shares_out` of `outcome_target`
+     *         since there is no closed form inverse of `calc_buy_amount` for pools with more than 2 outcomes the amount is found through bisection
+     * @param shares_out is the amount of shares the buyer wants to receive
+     * @param outcome_target is the outcome that is to be purchased
+     * @returns the smallest amount of collateral (fees included) for which `calc_buy_amount` returns at least `shares_out`
+     */
+    pub fn calc_buy_collateral_in(
+        &self,
+        shares_out: Balance,
+        outcome_target: u16
+    ) -> Balance {
+        assert!(outcome_target < self.outcomes, "ERR_INVALID_OUTCOME");
+        assert!(shares_out > 0, "ERR_ZERO_SHARES_OUT");
+
+        let balances = self.get_pool_balances();
+        assert!((outcome_target as usize) < balances.len(), "ERR_NO_TOKEN");
+
+        // Every unit of collateral that's left after fees buys at least one share, which gives us an upper bound to search in
+        // the +2 accounts for rounding in the fee and balance calculations
+        let mut high = math::complex_div_u128(self.collateral_denomination, shares_out, self.collateral_denomination - self.get_swap_fee()) + 2;
+        let mut low = 0;
 
-                new_buy_token_balance = math::complex_div_u128(self.collateral_denomination, dividend, divisor);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.calc_buy_amount_from_balances(&balances, mid, outcome_target) >= shares_out {
+                high = mid;
+            } else {
+                low = mid + 1;
             }
         }
-        assert!(new_buy_token_balance > 0, "ERR_MATH_APPROX");
 
-        token_to_buy_balance + collateral_in_minus_fees - new_buy_token_balance
+        assert!(self.calc_buy_amount_from_balances(&balances, high, outcome_target) >= shares_out, "ERR_MATH_APPROX");
+
+        high
+    }
+
+    /**
+     * @notice calculates the amount of shares out for `collateral_in` against a given set of pool balances
+     * @param balances are the pool balances to do the calculation on, indexed by outcome
+     * @param collateral_in is the amount of collateral to be used to calculate amount of shares out
+     * @param outcome_target is the outcome that is to be purchased
+     * @returns the amount of `outcome_target` shares out
+     */
+    fn calc_buy_amount_from_balances(
+        &self,
+        balances: &[Balance],
+        collateral_in: Balance,
+        outcome_target: u16
+    ) -> Balance {
+        let collateral_in_minus_fees = collateral_in - math::complex_mul_u128(self.collateral_denomination, collateral_in, self.get_swap_fee());
+        self.get_pricing_model().calc_buy_amount(balances, collateral_in_minus_fees, outcome_target)
     }
 
     pub fn calc_sell_collateral_out(
@@ -426,24 +822,154 @@ impl Pool {
     ) -> Balance {
         assert!(outcome_target <= self.outcomes, "ERR_INVALID_OUTCOME");
 
-        let outcome_tokens = &self.outcome_tokens;
-        let collateral_out_plus_fees = math::complex_div_u128(self.collateral_denomination, collateral_out, self.collateral_denomination - self.swap_fee);
-        let token_to_sell = outcome_tokens.get(&outcome_target).expect("ERR_NO_TOKEN");
-        let token_to_sell_balance = token_to_sell.get_balance(&env::current_account_id());
-        let mut new_sell_token_balance = token_to_sell_balance;
+        let balances = self.get_pool_balances();
+        assert!((outcome_target as usize) < balances.len(), "ERR_NO_TOKEN");
+        self.calc_sell_collateral_out_from_balances(&balances, collateral_out, outcome_target).expect("ERR_INSUFFICIENT_LIQUIDITY")
+    }
 
-        for (outcome, token) in outcome_tokens.iter() {
-            if outcome != outcome_target {
-                let balance = token.get_balance(&env::current_account_id());
-                let dividend = math::complex_mul_u128(self.collateral_denomination, new_sell_token_balance, balance);
-                let divisor = balance - collateral_out_plus_fees;
+    /**
+     * @notice calculates the amount of collateral a seller gets out for `shares_in`
+     *         since there is no closed form inverse of `calc_sell_collateral_out` for pools with more than 2 outcomes the amount is found through bisection
+     * @param shares_in is the amount of shares the seller wants to sell
+     * @param outcome_target is the outcome that is to be sold
+     * @returns the largest amount of collateral (after fees) for which `calc_sell_collateral_out` requires at most `shares_in`
+     */
+    pub fn calc_sell_shares_to_collateral(
+        &self,
+        shares_in: Balance,
+        outcome_target: u16
+    ) -> Balance {
+        assert!(outcome_target < self.outcomes, "ERR_INVALID_OUTCOME");
+        assert!(shares_in > 0, "ERR_ZERO_SHARES_IN");
 
-                new_sell_token_balance = math::complex_div_u128(self.collateral_denomination, dividend, divisor);
+        let balances = self.get_pool_balances();
+        assert!((outcome_target as usize) < balances.len(), "ERR_NO_TOKEN");
+
+        // Selling shares never returns more collateral than shares put in and can't take out more than the smallest opposing pool balance
+        // the -2 accounts for rounding when the fee is added back on top of the collateral out
+        let min_other_balance = balances.iter().enumerate()
+            .filter(|(outcome, _)| *outcome as u16 != outcome_target)
+            .map(|(_, balance)| *balance)
+            .min()
+            .unwrap();
+        let max_collateral_plus_fees = std::cmp::min(shares_in, min_other_balance.saturating_sub(2));
+        let mut high = math::complex_mul_u128(self.collateral_denomination, max_collateral_plus_fees, self.collateral_denomination - self.get_swap_fee());
+        let mut low = 0;
+
+        while low < high {
+            let mid = low + (high - low + 1) / 2;
+            let fits_shares_in = self.calc_sell_collateral_out_from_balances(&balances, mid, outcome_target).map_or(false, |required_shares_in| required_shares_in <= shares_in);
+            if fits_shares_in {
+                low = mid;
+            } else {
+                high = mid - 1;
             }
         }
-        assert!(new_sell_token_balance > 0, "ERR_MATH_APPROX");
 
-        collateral_out_plus_fees + new_sell_token_balance - token_to_sell_balance
+        low
+    }
+
+    /**
+     * @notice calculates the amount of shares that need to be put in to get `collateral_out` against a given set of pool balances
+     * @param balances are the pool balances to do the calculation on, indexed by outcome
+     * @param collateral_out is the amount of collateral the seller wants to receive
+     * @param outcome_target is the outcome that is to be sold
+     * @returns the amount of `outcome_target` shares in, `None` if the pool can't pay out `collateral_out`
+     */
+    fn calc_sell_collateral_out_from_balances(
+        &self,
+        balances: &[Balance],
+        collateral_out: Balance,
+        outcome_target: u16
+    ) -> Option<Balance> {
+        let collateral_out_plus_fees = math::complex_div_u128(self.collateral_denomination, collateral_out, self.collateral_denomination - self.get_swap_fee());
+        self.get_pricing_model().calc_sell_shares_in(balances, collateral_out_plus_fees, outcome_target)
+    }
+
+    /**
+     * @notice simulates a purchase on a copy of the pool balances, the balances change the same way `buy` changes them
+     * @param collateral_in is the amount of collateral to spend, fees included
+     * @param outcome_target is the outcome that is to be purchased
+     * @returns the quote for the purchase
+     */
+    pub fn quote_buy(
+        &self,
+        collateral_in: Balance,
+        outcome_target: u16
+    ) -> Quote {
+        assert!(outcome_target < self.outcomes, "ERR_INVALID_OUTCOME");
+        let mut balances = self.get_pool_balances();
+        let spot_price_before = self.get_pricing_model().get_spot_price(&balances, outcome_target);
+
+        let fee = math::complex_mul_u128(self.collateral_denomination, collateral_in, self.get_swap_fee());
+        let shares_out = self.calc_buy_amount_from_balances(&balances, collateral_in, outcome_target);
+        assert!(shares_out > 0, "ERR_ZERO_SHARES_OUT");
+
+        for balance in balances.iter_mut() {
+            *balance += collateral_in - fee;
+        }
+        balances[outcome_target as usize] -= shares_out;
+
+        let execution_price = math::simple_div_u128(self.collateral_denomination, collateral_in - fee, shares_out);
+        self.to_quote(&balances, collateral_in, shares_out, fee, execution_price.saturating_sub(spot_price_before), spot_price_before)
+    }
+
+    /**
+     * @notice simulates a sale on a copy of the pool balances, the balances change the same way `sell` changes them
+     * @param collateral_out is the amount of collateral to receive, fees excluded
+     * @param outcome_target is the outcome that is to be sold
+     * @returns the quote for the sale
+     */
+    pub fn quote_sell(
+        &self,
+        collateral_out: Balance,
+        outcome_target: u16
+    ) -> Quote {
+        assert!(outcome_target < self.outcomes, "ERR_INVALID_OUTCOME");
+        let mut balances = self.get_pool_balances();
+        let spot_price_before = self.get_pricing_model().get_spot_price(&balances, outcome_target);
+
+        let fee = math::complex_mul_u128(self.collateral_denomination, collateral_out, self.get_swap_fee());
+        let shares_in = self.calc_sell_collateral_out_from_balances(&balances, collateral_out, outcome_target).expect("ERR_INSUFFICIENT_LIQUIDITY");
+        assert!(shares_in > 0, "ERR_ZERO_SHARES_IN");
+
+        balances[outcome_target as usize] += shares_in;
+        for balance in balances.iter_mut() {
+            *balance = balance.checked_sub(collateral_out + fee).expect("ERR_INSUFFICIENT_LIQUIDITY");
+        }
+
+        let execution_price = math::simple_div_u128(self.collateral_denomination, collateral_out + fee, shares_in);
+        self.to_quote(&balances, collateral_out, shares_in, fee, spot_price_before.saturating_sub(execution_price), spot_price_before)
+    }
+
+    /**
+     * @param balances_after are the pool balances after the trade
+     * @param collateral is the collateral that's traded
+     * @param shares is the amount of shares that's traded
+     * @param fee is the swap fee paid on the trade
+     * @param price_delta is the absolute difference between the execution price without fees and `spot_price_before`
+     * @param spot_price_before is the spot price of the traded outcome without fees before the trade
+     * @returns the quote for the trade
+     */
+    fn to_quote(
+        &self,
+        balances_after: &[Balance],
+        collateral: Balance,
+        shares: Balance,
+        fee: Balance,
+        price_delta: Balance,
+        spot_price_before: Balance
+    ) -> Quote {
+        let pricing_model = self.get_pricing_model();
+        Quote {
+            collateral: U128(collateral),
+            shares: U128(shares),
+            fee: U128(fee),
+            average_price: U128(math::simple_div_u128(self.collateral_denomination, collateral, shares)),
+            price_impact: U128(math::simple_div_u128(self.collateral_denomination, price_delta, spot_price_before)),
+            spot_price_before: U128(spot_price_before),
+            spot_prices_after: (0..self.outcomes).map(|outcome| U128(pricing_model.get_spot_price(balances_after, outcome))).collect(),
+        }
     }
 
     pub fn buy(
@@ -451,8 +977,9 @@ impl Pool {
         sender: &AccountId,
         amount_in: Balance,
         outcome_target: u16,
-        min_shares_out: Balance
-    ) {
+        min_shares_out: Balance,
+        protocol_fee_share: u16
+    ) -> Balance {
 
         assert!(outcome_target < self.outcomes, "ERR_INVALID_OUTCOME");
 
@@ -462,8 +989,8 @@ impl Pool {
         let mut escrow_account = self.resolution_escrow.get_or_new(sender.to_string());
 
         // Transfer collateral in
-        let fee = math::complex_mul_u128(self.collateral_denomination, amount_in, self.swap_fee);
-        self.fee_pool_weight += fee;
+        let fee = math::complex_mul_u128(self.collateral_denomination, amount_in, self.get_swap_fee());
+        let (protocol_fee, creator_fee) = self.split_fee(fee, protocol_fee_share);
 
         let spent = escrow_account.add_to_spent(outcome_target, amount_in - fee);
         logger::log_account_outcome_spent(&self, sender, outcome_target, spent);
@@ -475,9 +1002,12 @@ impl Pool {
         token_out.safe_transfer_internal(&env::current_account_id(), sender, shares_out);
         self.outcome_tokens.insert(&outcome_target, &token_out);
         self.resolution_escrow.insert(sender, &escrow_account);
+        self.record_prices();
 
-        logger::log_buy(&self, &sender, outcome_target, amount_in, shares_out, fee);
+        logger::log_buy(&self, &sender, outcome_target, amount_in, shares_out, fee, protocol_fee, creator_fee);
         logger::log_pool(&self);
+
+        shares_out
     }
 
     pub fn sell(
@@ -485,7 +1015,8 @@ impl Pool {
         sender: &AccountId,
         amount_out: Balance,
         outcome_target: u16,
-        max_shares_in: Balance
+        max_shares_in: Balance,
+        protocol_fee_share: u16
     ) -> Balance {
 
         assert!(outcome_target < self.outcomes, "ERR_INVALID_OUTCOME");
@@ -499,14 +1030,14 @@ impl Pool {
         assert!(spent > 0, "account has no balance of outcome {} shares", outcome_target);
 
         // TODO: redo math and try to fit it into resolution_escrow
-        let fee = math::complex_mul_u128(self.collateral_denomination, amount_out, self.swap_fee);
+        let fee = math::complex_mul_u128(self.collateral_denomination, amount_out, self.get_swap_fee());
         let avg_price = math::complex_div_u128(self.collateral_denomination, spent, token_in.get_balance(sender));
         let sell_price = math::complex_div_u128(self.collateral_denomination, amount_out + fee, shares_in);
 
         token_in.transfer(&env::current_account_id(), shares_in);
         self.outcome_tokens.insert(&outcome_target, &token_in);
 
-        self.fee_pool_weight += fee;
+        let (protocol_fee, creator_fee) = self.split_fee(fee, protocol_fee_share);
 
         let to_escrow = match (sell_price).cmp(&avg_price) {
             Ordering::Less => {
@@ -549,13 +1080,132 @@ impl Pool {
         let tokens_to_burn = amount_out + fee;
         self.remove_from_pools(tokens_to_burn);
         self.resolution_escrow.insert(sender, &escrow_account);
+        self.record_prices();
 
-        logger::log_sell(&self, &env::predecessor_account_id(), outcome_target, shares_in, amount_out, fee, to_escrow);
+        logger::log_sell(&self, &sender, outcome_target, shares_in, amount_out, fee, protocol_fee, creator_fee, to_escrow);
         logger::log_pool(&self);
 
         to_escrow
     }
 
+    /**
+     * @notice swaps `shares_in` of `outcome_in` for shares of `outcome_out` by selling and buying within the pool
+     *         the collateral that's freed up by the sale (minus what's escrowed) is used for the purchase and never leaves the contract
+     * @param sender is the account that swaps its shares
+     * @param outcome_in is the outcome the sender sells
+     * @param outcome_out is the outcome the sender buys
+     * @param shares_in is the amount of `outcome_in` shares to sell
+     * @param min_shares_out is the minimum amount of `outcome_out` shares the sender expects out, this is to prevent slippage
+     * @param protocol_fee_share is the share of the swap fees that goes to the protocol, denominated in basis points
+     * @returns the amount of `outcome_out` shares bought
+     */
+    pub fn swap_outcomes(
+        &mut self,
+        sender: &AccountId,
+        outcome_in: u16,
+        outcome_out: u16,
+        shares_in: Balance,
+        min_shares_out: Balance,
+        protocol_fee_share: u16
+    ) -> Balance {
+        assert!(outcome_in != outcome_out, "ERR_SAME_OUTCOME");
+        assert!(outcome_out < self.outcomes, "ERR_INVALID_OUTCOME");
+
+        let collateral_out = self.calc_sell_shares_to_collateral(shares_in, outcome_in);
+        assert!(collateral_out > 0, "ERR_ZERO_COLLATERAL_OUT");
+
+        // Whatever is escrowed on the sale stays claimable by the sender on resolution, only the remainder is used to buy
+        let escrowed = self.sell(sender, collateral_out, outcome_in, shares_in, protocol_fee_share);
+        let collateral_in = collateral_out - escrowed;
+        assert!(collateral_in > 0, "ERR_ZERO_COLLATERAL_IN");
+
+        self.buy(sender, collateral_in, outcome_out, min_shares_out, protocol_fee_share)
+    }
+
+    /**
+     * @notice transfers outcome tokens from one account to another, the part of the sender's `spent` that belongs to the transferred shares moves along
+     *         so both accounts can reclaim their basis if the market turns out invalid
+     * @param sender is the account that's sending the shares
+     * @param receiver_id is the account that should receive the shares
+     * @param outcome is the outcome of the shares that are transferred
+     * @param amount of shares to transfer from sender to receiver
+     */
+    pub fn transfer_outcome_tokens(
+        &mut self,
+        sender: &AccountId,
+        receiver_id: &AccountId,
+        outcome: u16,
+        amount: Balance
+    ) {
+        assert_ne!(sender, receiver_id, "ERR_SELF_TRANSFER");
+        assert_ne!(receiver_id, &env::current_account_id(), "ERR_INVALID_RECEIVER");
+
+        let mut token = self.outcome_tokens.get(&outcome).expect("ERR_NO_OUTCOME");
+        let sender_balance = token.get_balance(sender);
+        assert!(amount > 0, "ERR_ZERO_AMOUNT");
+        assert!(amount <= sender_balance, "ERR_INSUFFICIENT_BALANCE");
+
+        let mut sender_escrow = self.resolution_escrow.get_or_new(sender.to_string());
+        let mut receiver_escrow = self.resolution_escrow.get_or_new(receiver_id.to_string());
+
+        // The spent that moves along is relative to the share of the sender's balance that's transferred: spent * amount / balance
+        // e.g. if Alice spent 6 collateral on 10 YES and transfers 5 YES to Bob, 3 of Alice's spent moves to Bob
+        let spent_to_move = math::simple_mul_u128(sender_balance, sender_escrow.get_spent(outcome), amount);
+
+        let sender_spent = sender_escrow.sub_from_spent(outcome, spent_to_move);
+        logger::log_account_outcome_spent(&self, sender, outcome, sender_spent);
+        let receiver_spent = receiver_escrow.add_to_spent(outcome, spent_to_move);
+        logger::log_account_outcome_spent(&self, receiver_id, outcome, receiver_spent);
+
+        token.safe_transfer_internal(sender, receiver_id, amount);
+        logger::log_share_transfer(&token.token, sender, receiver_id, amount);
+        self.outcome_tokens.insert(&outcome, &token);
+
+        self.resolution_escrow.insert(sender, &sender_escrow);
+        self.resolution_escrow.insert(receiver_id, &receiver_escrow);
+    }
+
+    /**
+     * @notice transfers pool tokens from one account to another, the sender's fees are settled and the part of the sender's `lp_spent`
+     *         that belongs to the transferred pool tokens moves along so both accounts can reclaim their basis if the market turns out invalid
+     * @param sender is the account that's sending the pool tokens
+     * @param receiver_id is the account that should receive the pool tokens
+     * @param amount of pool tokens to transfer from sender to receiver
+     * @returns the fees that were withdrawn for the sender and are to be transferred to them
+     */
+    pub fn transfer_pool_tokens(
+        &mut self,
+        sender: &AccountId,
+        receiver_id: &AccountId,
+        amount: Balance
+    ) -> Balance {
+        assert_ne!(sender, receiver_id, "ERR_SELF_TRANSFER");
+        assert_ne!(receiver_id, &env::current_account_id(), "ERR_INVALID_RECEIVER");
+
+        let sender_balance = self.pool_token.get_balance(sender);
+        assert!(amount > 0, "ERR_ZERO_AMOUNT");
+        assert!(amount <= sender_balance, "ERR_INSUFFICIENT_BALANCE");
+
+        let mut sender_escrow = self.resolution_escrow.get_expect(sender);
+        let mut receiver_escrow = self.resolution_escrow.get_or_new(receiver_id.to_string());
+
+        for outcome in 0..self.outcomes {
+            // The lp_spent that moves along is relative to the share of the sender's pool tokens that's transferred: lp_spent * amount / balance
+            let lp_spent_to_move = math::simple_mul_u128(sender_balance, sender_escrow.get_lp_spent(outcome), amount);
+            sender_escrow.sub_from_lp_spent(outcome, lp_spent_to_move);
+            receiver_escrow.add_to_lp_spent(outcome, lp_spent_to_move);
+        }
+
+        self.resolution_escrow.insert(sender, &sender_escrow);
+        self.resolution_escrow.insert(receiver_id, &receiver_escrow);
+
+        let fees = self.before_pool_token_transfer(Some(sender), Some(receiver_id), amount);
+        self.pool_token.safe_transfer_internal(sender, receiver_id, amount);
+        logger::log_share_transfer(&self.pool_token.token, sender, receiver_id, amount);
+
+        fees
+    }
+
     pub fn payout(
         &mut self,
         account_id: &AccountId,
@@ -593,6 +1243,101 @@ impl Pool {
     }
 
 
+    /**
+     * @notice splits a swap fee between the protocol, the market creator and the LPs
+     *         the creator's share is taken from what remains after the protocol fee so the shares never add up to more than `fee`
+     * @param fee is the total swap fee
+     * @param protocol_fee_share is the share of `fee` that goes to the protocol, denominated in basis points
+     * @returns the parts of `fee` that go to the protocol and to the market creator
+     */
+    fn split_fee(&mut self, fee: Balance, protocol_fee_share: u16) -> (Balance, Balance) {
+        let protocol_fee = fee * u128::from(protocol_fee_share) / u128::from(constants::FEE_SHARE_DENOMINATION);
+        let creator_fee = (fee - protocol_fee) * u128::from(self.creator_fee_share) / u128::from(constants::FEE_SHARE_DENOMINATION);
+        self.fee_pool_weight += fee - protocol_fee - creator_fee;
+        self.protocol_fees += protocol_fee;
+        self.creator_fees += creator_fee;
+        (protocol_fee, creator_fee)
+    }
+
+    /**
+     * @notice resets the creator fees so they can be paid out to the market creator
+     * @returns the creator fees accrued since the last claim
+     */
+    pub fn take_creator_fees(&mut self) -> Balance {
+        let creator_fees = self.creator_fees;
+        self.creator_fees = 0;
+        creator_fees
+    }
+
+    /**
+     * @notice resets the protocol fees the pool collected so they can be added to the contract's protocol balance
+     * @returns the protocol fees collected since the last call
+     */
+    pub fn take_protocol_fees(&mut self) -> Balance {
+        let protocol_fees = self.protocol_fees;
+        self.protocol_fees = 0;
+        protocol_fees
+    }
+
+    /**
+     * @notice starts a new emission period of the pool's reward program, creating the program if the pool doesn't have one yet
+     * @param reward_token_id is the account id of the token that's streamed to LPs, has to match the token of an existing program
+     * @param reward_rate is the amount of reward tokens to distribute per ms
+     * @param duration is the duration of the emission period in ms
+     */
+    pub fn fund_reward_program(
+        &mut self,
+        reward_token_id: AccountId,
+        reward_rate: Balance,
+        duration: u64
+    ) {
+        let pool_id = self.id;
+        let reward_program = self.reward_program.get_or_insert_with(|| RewardProgram::new(pool_id, reward_token_id.to_string()));
+        assert_eq!(reward_program.reward_token_id, reward_token_id, "ERR_REWARD_TOKEN_MISMATCH");
+        reward_program.start(self.pool_token.total_supply(), ns_to_ms(env::block_timestamp()), reward_rate, duration);
+    }
+
+    /**
+     * @param account_id is the account to return the claimable rewards of
+     * @returns the amount of reward tokens `account_id` can claim, 0 if the pool has no reward program
+     */
+    pub fn get_claimable_rewards(&self, account_id: &AccountId) -> Balance {
+        match &self.reward_program {
+            Some(reward_program) => {
+                let reward_per_share = reward_program.get_reward_per_share(self.pool_token.total_supply(), ns_to_ms(env::block_timestamp()));
+                reward_program.get_earned(account_id, self.pool_token.get_balance(account_id), reward_per_share)
+            },
+            None => 0
+        }
+    }
+
+    /**
+     * @notice resets the rewards `account_id` accrued so they can be paid out
+     * @param account_id is the account to claim the rewards for
+     * @returns the account id of the reward token and the amount of reward tokens to pay out
+     */
+    pub fn claim_rewards(&mut self, account_id: &AccountId) -> (AccountId, Balance) {
+        let reward_program = self.reward_program.as_mut().expect("ERR_NO_REWARD_PROGRAM");
+        let rewards = reward_program.claim(
+            account_id,
+            self.pool_token.get_balance(account_id),
+            self.pool_token.total_supply(),
+            ns_to_ms(env::block_timestamp())
+        );
+        (reward_program.reward_token_id.to_string(), rewards)
+    }
+
+    /**
+     * @notice resets the rewards that were emitted while the pool had no pool token supply so they can be paid out to `gov`
+     * @returns the account id of the reward token and the amount of reward tokens to pay out
+     */
+    pub fn take_undistributed_rewards(&mut self) -> (AccountId, Balance) {
+        let total_supply = self.pool_token.total_supply();
+        let reward_program = self.reward_program.as_mut().expect("ERR_NO_REWARD_PROGRAM");
+        let undistributed = reward_program.take_undistributed(total_supply, ns_to_ms(env::block_timestamp()));
+        (reward_program.reward_token_id.to_string(), undistributed)
+    }
+
     fn add_to_pools(&mut self, amount: Balance) {
         for outcome in 0..self.outcomes {
             let mut token = self.outcome_tokens.get(&outcome).expect("ERR_NO_OUTCOME");
@@ -610,6 +1355,18 @@ impl Pool {
         }
     }
 
+    /**
+     * @notice records the current spot prices in the price oracle, needs to be called after every change of the pool balances
+     *         a pool without liquidity has no prices, the oracle keeps the last prices until liquidity is added again
+     */
+    fn record_prices(&mut self) {
+        if self.pool_token.total_supply() == 0 {
+            return;
+        }
+        let prices = (0..self.outcomes).map(|outcome| self.get_spot_price_sans_fee(outcome)).collect();
+        self.price_oracle.record(ns_to_ms(env::block_timestamp()), prices);
+    }
+
     fn min_liquidity_amount(&self) -> Balance {
         self.collateral_denomination / 1_000_000
     }
@@ -623,21 +1380,8 @@ impl Pool {
         &self,
         target_outcome: u16
     ) -> Balance {
-
-        let mut odds_weight_for_target = 0;
-        let mut odds_weight_sum = 0;
-
-        for (outcome, _) in self.outcome_tokens.iter() {
-            let weight_for_outcome = self.get_odds_weight_for_outcome(outcome);
-            odds_weight_sum += weight_for_outcome;
-
-            if outcome == target_outcome {
-                odds_weight_for_target = weight_for_outcome;
-            }
-        }
-
-        let ratio = math::complex_div_u128(self.collateral_denomination, odds_weight_for_target, odds_weight_sum);
-        let scale = math::complex_div_u128(self.collateral_denomination, self.collateral_denomination, self.collateral_denomination - self.swap_fee);
+        let ratio = self.get_spot_price_sans_fee(target_outcome);
+        let scale = math::complex_div_u128(self.collateral_denomination, self.collateral_denomination, self.collateral_denomination - self.get_swap_fee());
 
         math::complex_mul_u128(self.collateral_denomination, ratio, scale)
     }
@@ -647,42 +1391,11 @@ impl Pool {
         &self,
         target_outcome: u16
     ) -> Balance {
-        let mut odds_weight_for_target = 0;
-        let mut odds_weight_sum = 0;
-
-        for (outcome, _) in self.outcome_tokens.iter() {
-            let weight_for_outcome = self.get_odds_weight_for_outcome(outcome);
-
-            odds_weight_sum += weight_for_outcome;
-
-            if outcome == target_outcome {
-                odds_weight_for_target = weight_for_outcome;
-            }
-        }
-
-        if odds_weight_sum == 0 {
+        let balances = self.get_pool_balances();
+        if (target_outcome as usize) >= balances.len() {
             return 0
         }
 
-        math::complex_div_u128(self.collateral_denomination, odds_weight_for_target, odds_weight_sum)
-    }
-
-    fn get_odds_weight_for_outcome(
-        &self,
-        target_outcome: u16
-    ) -> u128 {
-        let mut odds_weight_for_target = 0;
-
-        for (outcome, token) in self.outcome_tokens.iter() {
-            if outcome != target_outcome {
-                let balance = token.get_balance(&env::current_account_id());
-                odds_weight_for_target = if odds_weight_for_target == 0 {
-                    balance
-                } else {
-                    math::complex_mul_u128(self.collateral_denomination, odds_weight_for_target, balance)
-                };
-            }
-        }
-        odds_weight_for_target
+        self.get_pricing_model().get_spot_price(&balances, target_outcome)
     }
 }
@@ -1,5 +1,6 @@
 use near_sdk::AccountId;
-use crate::pool::Pool;
+use crate::pool::{Pool, FeeSchedule};
+use crate::pricing_model::PricingModelType;
 use near_sdk::Balance;
 
 // TODO: remove, completely redunant function
@@ -13,12 +14,18 @@ pub fn new_pool(
     collateral_token_id: AccountId,
     collateral_decimals: u32,
     swap_fee: Balance,
+    pricing_model: PricingModelType,
+    fee_schedule: Option<FeeSchedule>,
+    creator_fee_share: u16,
 ) -> Pool {
     Pool::new(
         pool_id,
         collateral_token_id,
         collateral_decimals,
         outcomes,
-        swap_fee
+        swap_fee,
+        pricing_model,
+        fee_schedule,
+        creator_fee_share
     )
 }
@@ -0,0 +1,268 @@
+use crate::*;
+
+/**
+ * @notice the cumulative prices of a pool's outcomes at a point in time
+ */
+#[derive(BorshDeserialize, BorshSerialize, Clone)]
+pub struct Observation {
+    pub timestamp: Timestamp, // time in ms of the observation
+    pub price_cumulatives: Vec<u128>, // per outcome, the sum of the spot price multiplied by the ms it was in effect up to `timestamp`, wraps on overflow
+    pub prices: Vec<Balance>, // per outcome, the spot price that's in effect from `timestamp` on
+}
+
+impl Observation {
+    /**
+     * @param timestamp is the time in ms to extrapolate to, needs to be at or after the observation
+     * @returns the cumulative prices at `timestamp` assuming the prices didn't change since the observation
+     */
+    pub fn cumulatives_at(&self, timestamp: Timestamp) -> Vec<u128> {
+        let elapsed = u128::from(timestamp - self.timestamp);
+        self.price_cumulatives
+            .iter()
+            .zip(self.prices.iter())
+            .map(|(cumulative, price)| cumulative.wrapping_add(price * elapsed))
+            .collect()
+    }
+}
+
+/**
+ * @notice keeps time-weighted price accumulators for a pool so the average price over a window can't be moved by a single trade
+ *         every price change is recorded as an observation, changes within the same block replace that block's observation, the most recent observations are kept in a ring buffer
+ */
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct PriceOracle {
+    pub observations: Vector<Observation>, // ring buffer of past observations, holds at most `MAX_TWAP_OBSERVATIONS`
+    pub next_index: u64, // index in `observations` that's overwritten next once the buffer is full, which is the oldest observation
+}
+
+impl PriceOracle {
+    /**
+     * @notice create a new price oracle without any observations
+     * @param pool_id is the id of the pool the oracle belongs to, used for unique storage pointers
+     * @returns a new `PriceOracle` instance
+     */
+    pub fn new(pool_id: u64) -> Self {
+        Self {
+            observations: Vector::new(format!("p{}ob", pool_id).as_bytes().to_vec()),
+            next_index: 0,
+        }
+    }
+
+    /**
+     * @notice accumulates the prices that were in effect since the newest observation and records the new prices
+     * @param now is the current time in ms
+     * @param prices are the spot prices per outcome that are in effect from `now` on
+     */
+    pub fn record(&mut self, now: Timestamp, prices: Vec<Balance>) {
+        let len = self.observations.len();
+        let newest = self.get_observation(len.saturating_sub(1));
+
+        // Only the prices at the end of a block are in effect for any amount of time
+        if let Some(newest) = &newest {
+            if newest.timestamp == now {
+                let observation = Observation { prices, ..newest.clone() };
+                self.observations.replace((self.next_index + len - 1) % len, &observation);
+                return;
+            }
+        }
+
+        let observation = Observation {
+            timestamp: now,
+            price_cumulatives: match newest {
+                Some(newest) => newest.cumulatives_at(now),
+                None => vec![0; prices.len()]
+            },
+            prices
+        };
+        if len < constants::MAX_TWAP_OBSERVATIONS {
+            self.observations.push(&observation);
+        } else {
+            self.observations.replace(self.next_index, &observation);
+            self.next_index = (self.next_index + 1) % constants::MAX_TWAP_OBSERVATIONS;
+        }
+    }
+
+    /**
+     * @param index is the position of the observation in chronological order, 0 being the oldest
+     * @returns the observation at `index` or `None` if there are less observations
+     */
+    fn get_observation(&self, index: u64) -> Option<Observation> {
+        let len = self.observations.len();
+        if index >= len {
+            return None;
+        }
+        self.observations.get((self.next_index + index) % len)
+    }
+
+    /**
+     * @notice the prices are constant between two observations so the cumulative prices are extrapolated from the newest observation at or before `timestamp`
+     * @param timestamp is the time in ms to return the cumulative prices at, can't be before the oldest observation
+     * @param now is the current time in ms
+     * @returns the cumulative prices per outcome at `timestamp`
+     */
+    pub fn get_cumulatives_at(&self, timestamp: Timestamp, now: Timestamp) -> Vec<u128> {
+        assert!(timestamp <= now, "ERR_INVALID_TIMESTAMP");
+        let oldest = self.get_observation(0).expect("ERR_NO_OBSERVATIONS");
+        assert!(timestamp >= oldest.timestamp, "ERR_OBSERVATION_TOO_OLD");
+
+        // Binary search for the newest observation at or before `timestamp`
+        let mut low = 0;
+        let mut high = self.observations.len() - 1;
+        while low < high {
+            let mid = (low + high + 1) / 2;
+            if self.get_observation(mid).unwrap().timestamp <= timestamp {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+
+        self.get_observation(low).unwrap().cumulatives_at(timestamp)
+    }
+
+    /**
+     * @param outcome is the outcome to return the average price of
+     * @param window is the amount of ms before `now` to average the price over
+     * @param now is the current time in ms
+     * @returns the time-weighted average spot price of `outcome` over `window`
+     */
+    pub fn get_twap(&self, outcome: u16, window: u64, now: Timestamp) -> Balance {
+        assert!(window > 0, "ERR_INVALID_WINDOW");
+        let start = now.checked_sub(window).expect("ERR_OBSERVATION_TOO_OLD");
+        let start_cumulative = self.get_cumulatives_at(start, now)[outcome as usize];
+        let end_cumulative = self.get_cumulatives_at(now, now)[outcome as usize];
+        end_cumulative.wrapping_sub(start_cumulative) / u128::from(window)
+    }
+}
+
+/**
+ * @notice public representation of the cumulative prices at a point in time
+ */
+#[derive(Serialize, Deserialize)]
+pub struct ObservationView {
+    pub timestamp: WrappedTimestamp,
+    pub price_cumulatives: Vec<WrappedBalance>,
+}
+
+#[near_bindgen]
+impl AMMContract {
+    /**
+     * @param market_id is the index of the market to retrieve data from
+     * @param outcome is the outcome to return the average price of
+     * @param window is the amount of ms to average the price over, ending now
+     * @returns the wrapped time-weighted average spot price of `outcome`, excluding fees
+     */
+    pub fn get_twap(&self, market_id: U64, outcome: u16, window: U64) -> WrappedBalance {
+        let market = self.get_market_expect(market_id);
+        assert!(outcome < market.pool.outcomes, "ERR_INVALID_OUTCOME");
+        U128(market.pool.price_oracle.get_twap(outcome, window.into(), ns_to_ms(env::block_timestamp())))
+    }
+
+    /**
+     * @notice returns the cumulative prices at several points in the past so callers can compute averages over their own windows,
+     *         the average price between two observations is the difference of their cumulatives, with wrapping subtraction, divided by the time between them
+     * @param market_id is the index of the market to retrieve data from
+     * @param ms_agos are the amounts of ms before now to return the cumulative prices at
+     * @returns an observation for each of `ms_agos`
+     */
+    pub fn observe(&self, market_id: U64, ms_agos: Vec<U64>) -> Vec<ObservationView> {
+        let market = self.get_market_expect(market_id);
+        let now = ns_to_ms(env::block_timestamp());
+        ms_agos
+            .into_iter()
+            .map(|ms_ago| {
+                let timestamp = now.checked_sub(ms_ago.into()).expect("ERR_OBSERVATION_TOO_OLD");
+                ObservationView {
+                    timestamp: U64(timestamp),
+                    price_cumulatives: market.pool.price_oracle
+                        .get_cumulatives_at(timestamp, now)
+                        .into_iter()
+                        .map(U128)
+                        .collect()
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod price_oracle_tests {
+    use super::*;
+    use near_sdk::{ MockedBlockchain };
+    use near_sdk::{ testing_env, VMContext };
+
+    fn alice() -> AccountId {
+        "alice.near".to_string()
+    }
+
+    fn get_context() -> VMContext {
+        VMContext {
+            current_account_id: alice(),
+            signer_account_id: alice(),
+            signer_account_pk: vec![0, 1, 2],
+            predecessor_account_id: alice(),
+            input: vec![],
+            block_index: 0,
+            block_timestamp: 0,
+            account_balance: 1000 * 10u128.pow(24),
+            account_locked_balance: 0,
+            storage_usage: 10u64.pow(6),
+            attached_deposit: 0,
+            prepaid_gas: 10u64.pow(18),
+            random_seed: vec![0, 1, 2],
+            is_view: false,
+            output_data_receivers: vec![],
+            epoch_height: 0,
+        }
+    }
+
+    #[test]
+    fn twap_weights_prices_by_time() {
+        testing_env!(get_context());
+        let mut oracle = PriceOracle::new(0);
+
+        oracle.record(0, vec![60, 40]);
+        oracle.record(100, vec![20, 80]);
+
+        assert_eq!(oracle.get_twap(0, 200, 200), 40);
+        assert_eq!(oracle.get_twap(1, 200, 200), 60);
+        assert_eq!(oracle.get_twap(0, 100, 200), 20);
+    }
+
+    #[test]
+    fn twap_uses_every_price_change() {
+        testing_env!(get_context());
+        let mut oracle = PriceOracle::new(0);
+
+        // The price changes halfway through the window, the first price of a block is overwritten by its last
+        oracle.record(0, vec![50, 50]);
+        oracle.record(0, vec![60, 40]);
+        oracle.record(500, vec![20, 80]);
+        oracle.record(1000, vec![20, 80]);
+        assert_eq!(oracle.observations.len(), 3);
+
+        assert_eq!(oracle.get_twap(0, 1000, 1000), 40);
+        assert_eq!(oracle.get_twap(0, 500, 1000), 20);
+        assert_eq!(oracle.get_cumulatives_at(250, 1000), vec![60 * 250, 40 * 250]);
+        assert_eq!(oracle.get_cumulatives_at(750, 1000), vec![60 * 500 + 20 * 250, 40 * 500 + 80 * 250]);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_OBSERVATION_TOO_OLD")]
+    fn twap_window_before_oldest_observation() {
+        testing_env!(get_context());
+        let mut oracle = PriceOracle::new(0);
+        let interval = 1000;
+
+        for i in 0..=constants::MAX_TWAP_OBSERVATIONS {
+            oracle.record(1000 + i * interval, vec![50, 50]);
+        }
+        assert_eq!(oracle.observations.len(), constants::MAX_TWAP_OBSERVATIONS);
+
+        // The first observation has been overwritten
+        let now = 1000 + constants::MAX_TWAP_OBSERVATIONS * interval;
+        assert_eq!(oracle.get_twap(0, now - 1000 - interval, now), 50);
+        oracle.get_twap(0, now - 1000, now);
+    }
+}
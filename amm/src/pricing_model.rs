@@ -0,0 +1,381 @@
+use crate::*;
+use crate::math::FIXED_ONE;
+
+/*** Market maker curves that a pool can use to price its outcome tokens ***/
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum PricingModelType {
+    Cpmm, // constant product market maker
+    Lmsr, // logarithmic market scoring rule
+}
+
+impl Default for PricingModelType {
+    fn default() -> Self {
+        PricingModelType::Cpmm
+    }
+}
+
+/**
+ * @notice a pricing model prices outcome tokens against the pool balances, swap fees are handled by the `Pool`
+ */
+pub trait PricingModel {
+    /**
+     * @param balances are the pool balances indexed by outcome
+     * @param collateral_in is the amount of collateral, after fees, that's used to buy
+     * @param outcome_target is the outcome that is to be purchased
+     * @returns the amount of `outcome_target` shares out
+     */
+    fn calc_buy_amount(&self, balances: &[Balance], collateral_in: Balance, outcome_target: u16) -> Balance;
+
+    /**
+     * @param balances are the pool balances indexed by outcome
+     * @param collateral_out is the amount of collateral, including fees, that's taken out of the pool
+     * @param outcome_target is the outcome that is to be sold
+     * @returns the amount of `outcome_target` shares in, `None` if the pool doesn't hold enough liquidity to pay out `collateral_out`
+     */
+    fn calc_sell_shares_in(&self, balances: &[Balance], collateral_out: Balance, outcome_target: u16) -> Option<Balance>;
+
+    /**
+     * @param balances are the pool balances indexed by outcome
+     * @param outcome_target is the outcome to price
+     * @returns the marginal price of `outcome_target` without fees, denominated in the collateral denomination
+     */
+    fn get_spot_price(&self, balances: &[Balance], outcome_target: u16) -> Balance;
+}
+
+/**
+ * @notice constant product market maker, the product of all pool balances stays constant on trades
+ */
+pub struct Cpmm {
+    pub collateral_denomination: u128,
+}
+
+impl PricingModel for Cpmm {
+    fn calc_buy_amount(&self, balances: &[Balance], collateral_in: Balance, outcome_target: u16) -> Balance {
+        let token_to_buy_balance = balances[outcome_target as usize];
+        let mut new_buy_token_balance = token_to_buy_balance;
+
+        for (outcome, balance) in balances.iter().enumerate() {
+            if outcome as u16 != outcome_target {
+                let dividend = math::complex_mul_u128(self.collateral_denomination, new_buy_token_balance, *balance);
+                let divisor = balance + collateral_in;
+
+                new_buy_token_balance = math::complex_div_u128(self.collateral_denomination, dividend, divisor);
+            }
+        }
+        assert!(new_buy_token_balance > 0, "ERR_MATH_APPROX");
+
+        token_to_buy_balance + collateral_in - new_buy_token_balance
+    }
+
+    fn calc_sell_shares_in(&self, balances: &[Balance], collateral_out: Balance, outcome_target: u16) -> Option<Balance> {
+        let token_to_sell_balance = balances[outcome_target as usize];
+        let mut new_sell_token_balance = token_to_sell_balance;
+
+        for (outcome, balance) in balances.iter().enumerate() {
+            if outcome as u16 != outcome_target {
+                if *balance <= collateral_out {
+                    return None;
+                }
+                let dividend = math::complex_mul_u128(self.collateral_denomination, new_sell_token_balance, *balance);
+                let divisor = balance - collateral_out;
+
+                new_sell_token_balance = math::complex_div_u128(self.collateral_denomination, dividend, divisor);
+            }
+        }
+        assert!(new_sell_token_balance > 0, "ERR_MATH_APPROX");
+
+        Some(collateral_out + new_sell_token_balance - token_to_sell_balance)
+    }
+
+    fn get_spot_price(&self, balances: &[Balance], outcome_target: u16) -> Balance {
+        let mut odds_weight_for_target = 0;
+        let mut odds_weight_sum = 0;
+
+        for outcome in 0..balances.len() as u16 {
+            let weight_for_outcome = self.get_odds_weight_for_outcome(balances, outcome);
+            odds_weight_sum += weight_for_outcome;
+
+            if outcome == outcome_target {
+                odds_weight_for_target = weight_for_outcome;
+            }
+        }
+
+        if odds_weight_sum == 0 {
+            return 0
+        }
+
+        math::complex_div_u128(self.collateral_denomination, odds_weight_for_target, odds_weight_sum)
+    }
+}
+
+impl Cpmm {
+    /**
+     * @returns the product of the balances of all outcomes other than `outcome_target`
+     */
+    fn get_odds_weight_for_outcome(&self, balances: &[Balance], outcome_target: u16) -> u128 {
+        let mut odds_weight_for_target = 0;
+
+        for (outcome, balance) in balances.iter().enumerate() {
+            if outcome as u16 != outcome_target {
+                odds_weight_for_target = if odds_weight_for_target == 0 {
+                    *balance
+                } else {
+                    math::complex_mul_u128(self.collateral_denomination, odds_weight_for_target, *balance)
+                };
+            }
+        }
+        odds_weight_for_target
+    }
+}
+
+const MIN_WEIGHT: u128 = 1; // smallest fixed-point weight, e^(-balance / liquidity) is 0 beyond a ratio of about 41
+
+/**
+ * @notice logarithmic market scoring rule expressed over the pool balances, on trades the sum of e^(-balance / liquidity) stays constant
+ *         the price of an outcome is e^(-balance / liquidity) divided by that sum, so it depends on the difference between balances
+ *         rather than their ratio and the pool can offer good prices for many outcomes without a lot of liquidity
+ */
+pub struct Lmsr {
+    pub collateral_denomination: u128,
+    pub liquidity: u128, // the liquidity parameter `b`, denominated in collateral
+}
+
+impl PricingModel for Lmsr {
+    fn calc_buy_amount(&self, balances: &[Balance], collateral_in: Balance, outcome_target: u16) -> Balance {
+        let offset = *balances.iter().min().unwrap();
+        let weights = self.get_weights(balances, offset);
+        let invariant: u128 = weights.iter().sum();
+        let other_weights = invariant - weights[outcome_target as usize];
+
+        // Minting `collateral_in` of every outcome into the pool multiplies the weights of the other outcomes by e^(-collateral_in / liquidity)
+        let mint_factor = math::exp_neg(self.to_fixed(collateral_in));
+        let new_other_weights = math::simple_mul_u128(FIXED_ONE, other_weights, mint_factor);
+
+        // The pool keeps at least 1 of every outcome token, even if a buy would take out (nearly) all of them
+        let new_buy_token_balance = std::cmp::max(self.balance_for_weight(invariant - new_other_weights, offset), 1);
+
+        (balances[outcome_target as usize] + collateral_in).saturating_sub(new_buy_token_balance)
+    }
+
+    fn calc_sell_shares_in(&self, balances: &[Balance], collateral_out: Balance, outcome_target: u16) -> Option<Balance> {
+        // Burning `collateral_out` of every outcome from the pool, the outcome that's sold is topped up by the shares in
+        let mut offset = balances[outcome_target as usize];
+        for (outcome, balance) in balances.iter().enumerate() {
+            if outcome as u16 != outcome_target {
+                if *balance <= collateral_out {
+                    return None;
+                }
+                offset = std::cmp::min(offset, balance - collateral_out);
+            }
+        }
+
+        let invariant: u128 = self.get_weights(balances, offset).iter().sum();
+        let new_other_weights: u128 = balances.iter().enumerate()
+            .filter(|(outcome, _)| *outcome as u16 != outcome_target)
+            .map(|(_, balance)| math::exp_neg(self.to_fixed(balance - collateral_out - offset)))
+            .sum();
+
+        if invariant <= new_other_weights {
+            return None;
+        }
+
+        let new_sell_token_balance = self.balance_for_weight(invariant - new_other_weights, offset);
+
+        Some((new_sell_token_balance + collateral_out).saturating_sub(balances[outcome_target as usize]))
+    }
+
+    fn get_spot_price(&self, balances: &[Balance], outcome_target: u16) -> Balance {
+        // The pool has no liquidity
+        if self.liquidity == 0 {
+            return 0
+        }
+
+        let weights = self.get_weights(balances, *balances.iter().min().unwrap());
+        let weight_sum: u128 = weights.iter().sum();
+
+        if weight_sum == 0 {
+            return 0
+        }
+
+        math::simple_div_u128(self.collateral_denomination, weights[outcome_target as usize], weight_sum)
+    }
+}
+
+impl Lmsr {
+    /**
+     * @notice calculates the liquidity parameter and pool balances for the first liquidity added to a pool
+     *         the liquidity parameter is chosen so that the weights sum up to exactly 1 and the cheapest outcome holds `total_in`
+     * @param total_in is the amount of collateral that's added
+     * @param weights are the odds weights where the price of an outcome is inversely proportional to its weight
+     * @returns the liquidity parameter and the pool balance of each outcome
+     */
+    pub fn initial_balances(total_in: Balance, weights: &[u128]) -> (u128, Vec<Balance>) {
+        // price_i = 1 / (weight_i * sum(1 / weight_j)) = 1 / sum(weight_i / weight_j)
+        let neg_ln_prices: Vec<u128> = weights.iter().map(|weight_i| {
+            let inverse_price: u128 = weights.iter().map(|weight_j| math::simple_div_u128(FIXED_ONE, *weight_i, *weight_j)).sum();
+            let price = math::simple_div_u128(FIXED_ONE, FIXED_ONE, inverse_price);
+            // Weights that are too skewed would price an outcome below the fixed-point precision
+            assert!(price > 0, "ERR_INVALID_WEIGHTS");
+            let neg_ln_price = -math::ln(price);
+            assert!(neg_ln_price > 0, "ERR_INVALID_WEIGHTS");
+            neg_ln_price as u128
+        }).collect();
+
+        // balance_i = -liquidity * ln(price_i), with the largest balance equal to `total_in`
+        let max_neg_ln_price = *neg_ln_prices.iter().max().unwrap();
+        let liquidity = math::simple_div_u128(FIXED_ONE, total_in, max_neg_ln_price);
+        let balances = neg_ln_prices.iter().map(|neg_ln_price| math::simple_mul_u128(max_neg_ln_price, total_in, *neg_ln_price)).collect();
+
+        (liquidity, balances)
+    }
+
+    /**
+     * @notice prices only depend on the differences between balances, so the weights are taken relative to an `offset` no larger than any balance
+     *         which keeps the largest weight from underflowing when balances grow large compared to the liquidity parameter
+     * @param balances are the pool balances indexed by outcome
+     * @param offset is subtracted from every balance
+     * @returns e^(-(balance - offset) / liquidity) for every balance as fixed-point numbers
+     */
+    fn get_weights(&self, balances: &[Balance], offset: Balance) -> Vec<u128> {
+        balances.iter().map(|balance| math::exp_neg(self.to_fixed(balance - offset))).collect()
+    }
+
+    /**
+     * @returns `amount / liquidity` as a fixed-point number
+     */
+    fn to_fixed(&self, amount: Balance) -> u128 {
+        assert!(self.liquidity > 0, "ERR_NO_LIQUIDITY");
+        math::simple_div_u128(FIXED_ONE, amount, self.liquidity)
+    }
+
+    /**
+     * @notice weights below `MIN_WEIGHT` underflowed and are clamped, which caps the balance at about 41 times the liquidity above `offset`
+     * @param weight is a weight relative to `offset`
+     * @param offset is the offset the weight was taken relative to
+     * @returns the balance for which e^(-(balance - offset) / liquidity) equals `weight`: offset - liquidity * ln(weight), at least 0
+     */
+    fn balance_for_weight(&self, weight: u128, offset: Balance) -> Balance {
+        let ln_weight = math::ln(std::cmp::max(weight, MIN_WEIGHT));
+        let delta = math::simple_mul_u128(FIXED_ONE, self.liquidity, ln_weight.abs() as u128);
+        if ln_weight <= 0 {
+            offset + delta
+        } else {
+            offset.saturating_sub(delta)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_token_denom(amount: u128) -> u128 {
+        amount * 10_u128.pow(24)
+    }
+
+    fn assert_approx_eq(a: u128, b: u128, tolerance: u128) {
+        let diff = if a > b { a - b } else { b - a };
+        assert!(diff <= tolerance, "{} and {} differ by more than {}", a, b, tolerance);
+    }
+
+    #[test]
+    fn exp_neg_and_ln_test() {
+        assert_eq!(math::exp_neg(0), FIXED_ONE);
+        assert_approx_eq(math::exp_neg(FIXED_ONE), 367_879_441_171_442_321, 100);
+        assert_approx_eq(math::exp_neg(10 * FIXED_ONE), 45_399_929_762_484, 100);
+        assert_eq!(math::ln(FIXED_ONE), 0);
+        assert_approx_eq(math::ln(2 * FIXED_ONE) as u128, 693_147_180_559_945_309, 100);
+        assert_approx_eq((-math::ln(FIXED_ONE / 4)) as u128, 1_386_294_361_119_890_618, 100);
+        assert_approx_eq((-math::ln(math::exp_neg(5 * FIXED_ONE))) as u128, 5 * FIXED_ONE, 1_000_000);
+    }
+
+    #[test]
+    fn lmsr_initial_balances_test() {
+        let total_in = to_token_denom(100);
+        let (liquidity, balances) = Lmsr::initial_balances(total_in, &[1, 1, 1, 1]);
+        let lmsr = Lmsr { collateral_denomination: to_token_denom(1), liquidity };
+
+        assert_eq!(balances, vec![total_in; 4]);
+        for outcome in 0..4 {
+            assert_approx_eq(lmsr.get_spot_price(&balances, outcome), to_token_denom(1) / 4, 1_000_000_000_000);
+        }
+
+        let (liquidity, balances) = Lmsr::initial_balances(total_in, &[3, 1]);
+        let lmsr = Lmsr { collateral_denomination: to_token_denom(1), liquidity };
+        assert_eq!(balances[0], total_in);
+        assert_approx_eq(lmsr.get_spot_price(&balances, 0), to_token_denom(1) / 4, 1_000_000_000_000);
+        assert_approx_eq(lmsr.get_spot_price(&balances, 1), to_token_denom(1) * 3 / 4, 1_000_000_000_000);
+    }
+
+    #[test]
+    fn lmsr_buy_sell_round_trip_test() {
+        let (liquidity, balances) = Lmsr::initial_balances(to_token_denom(100), &[1, 1, 1, 1, 1, 1, 1, 1]);
+        let lmsr = Lmsr { collateral_denomination: to_token_denom(1), liquidity };
+        let collateral_in = to_token_denom(10);
+
+        let shares_out = lmsr.calc_buy_amount(&balances, collateral_in, 2);
+        assert!(shares_out > collateral_in * 4);
+
+        let new_balances: Vec<Balance> = balances.iter().enumerate().map(|(outcome, balance)| {
+            if outcome == 2 { balance + collateral_in - shares_out } else { balance + collateral_in }
+        }).collect();
+        assert!(lmsr.get_spot_price(&new_balances, 2) > lmsr.get_spot_price(&balances, 2));
+
+        let shares_in = lmsr.calc_sell_shares_in(&new_balances, collateral_in, 2).unwrap();
+        assert_approx_eq(shares_in, shares_out, to_token_denom(1) / 1_000_000);
+
+        assert!(lmsr.calc_sell_shares_in(&new_balances, to_token_denom(200), 2).is_none());
+    }
+
+    #[test]
+    fn lmsr_extreme_balance_ratio_test() {
+        let (liquidity, balances) = Lmsr::initial_balances(to_token_denom(100), &[1, 1]);
+        let lmsr = Lmsr { collateral_denomination: to_token_denom(1), liquidity };
+
+        // A buy of ~70 times the liquidity parameter leaves the other outcome's weight far below the fixed-point precision
+        let collateral_in = to_token_denom(10_000);
+        let shares_out = lmsr.calc_buy_amount(&balances, collateral_in, 0);
+        assert!(shares_out < balances[0] + collateral_in);
+        assert_approx_eq(shares_out, balances[0] + collateral_in, to_token_denom(1) / 1_000_000);
+
+        let new_balances = vec![balances[0] + collateral_in - shares_out, balances[1] + collateral_in];
+        assert_approx_eq(lmsr.get_spot_price(&new_balances, 0), to_token_denom(1), 1_000_000);
+        assert_eq!(lmsr.get_spot_price(&new_balances, 1), 0);
+
+        // Buying the outcome that's priced at ~0 doesn't take the log of an underflowed weight
+        let longshot_shares_out = lmsr.calc_buy_amount(&new_balances, to_token_denom(1), 1);
+        assert!(longshot_shares_out > to_token_denom(1));
+        assert!(longshot_shares_out < new_balances[1] + to_token_denom(1));
+        assert!(lmsr.calc_buy_amount(&new_balances, 1, 1) > 0);
+
+        let shares_in = lmsr.calc_sell_shares_in(&new_balances, to_token_denom(1), 0).unwrap();
+        assert_approx_eq(shares_in, to_token_denom(1), 1_000_000);
+        // The pool holds (almost) no tokens of the winning outcome to burn for a sale of the other outcome
+        assert!(lmsr.calc_sell_shares_in(&new_balances, to_token_denom(1), 1).is_none());
+    }
+
+    #[test]
+    fn lmsr_skewed_initial_weights_test() {
+        let (liquidity, balances) = Lmsr::initial_balances(to_token_denom(100), &[1, 1_000_000_000_000]);
+        let lmsr = Lmsr { collateral_denomination: to_token_denom(1), liquidity };
+        // The price of an outcome is inversely proportional to its weight
+        assert!(lmsr.get_spot_price(&balances, 0) > to_token_denom(1) * 999 / 1000);
+
+        let shares_out = lmsr.calc_buy_amount(&balances, to_token_denom(1), 1);
+        assert!(shares_out > to_token_denom(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INVALID_WEIGHTS")]
+    fn lmsr_weights_beyond_precision_test() {
+        Lmsr::initial_balances(to_token_denom(100), &[1, 10_u128.pow(20)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NO_LIQUIDITY")]
+    fn lmsr_zero_liquidity_test() {
+        let lmsr = Lmsr { collateral_denomination: to_token_denom(1), liquidity: 0 };
+        lmsr.calc_buy_amount(&[to_token_denom(1), to_token_denom(1)], to_token_denom(1), 0);
+    }
+}
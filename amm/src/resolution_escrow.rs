@@ -27,6 +27,38 @@ impl OutcomeToBalanceMap {
     }
 }
 
+/**
+ * @notice index of the markets each account holds a resolution escrow in, it's shared by the escrows of all pools through a fixed storage prefix
+ */
+pub struct AccountMarkets(LookupMap<AccountId, UnorderedSet<u64>>);
+
+impl AccountMarkets {
+    pub fn new() -> Self {
+        Self(LookupMap::new(vec![constants::MARKETS_BY_ACCOUNT_TAG]))
+    }
+
+    pub fn get(&self, account_id: &AccountId) -> Option<UnorderedSet<u64>> {
+        self.0.get(account_id)
+    }
+
+    pub fn add(&mut self, account_id: &AccountId, market_id: u64) {
+        let mut markets = self.0
+            .get(account_id)
+            .unwrap_or_else(|| UnorderedSet::new(nested_storage_prefix(constants::ACCOUNT_MARKETS_TAG, account_id.as_bytes())));
+        if markets.insert(&market_id) {
+            self.0.insert(account_id, &markets);
+        }
+    }
+
+    pub fn remove(&mut self, account_id: &AccountId, market_id: u64) {
+        if let Some(mut markets) = self.0.get(account_id) {
+            if markets.remove(&market_id) {
+                self.0.insert(account_id, &markets);
+            }
+        }
+    }
+}
+
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct ResolutionEscrows { 
     escrow_accounts: LookupMap<AccountId, ResolutionEscrow>,
@@ -56,10 +88,12 @@ impl ResolutionEscrows {
     
     pub fn remove(&mut self, account_id: &AccountId ) {
         self.escrow_accounts.remove(account_id);
+        AccountMarkets::new().remove(account_id, self.pool_id);
     }
 
     pub fn insert(&mut self, account_id: &AccountId, escrow_account: &ResolutionEscrow) {
         self.escrow_accounts.insert(account_id, escrow_account);
+        AccountMarkets::new().add(account_id, self.pool_id);
     }
 }
 
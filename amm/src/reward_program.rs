@@ -0,0 +1,373 @@
+use crate::*;
+
+/**
+ * @notice streams a reward token to the LPs of a pool, rewards accrue pro-rata to pool token balances through a reward per share accumulator
+ */
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct RewardProgram {
+    pub reward_token_id: AccountId, // account id of the token that's streamed to LPs
+    pub reward_rate: Balance, // amount of reward tokens distributed per ms
+    pub start_time: Timestamp, // time in ms at which the current emission period started
+    pub end_time: Timestamp, // time in ms at which the current emission period ends
+    pub last_update_time: Timestamp, // time in ms up to which `reward_per_share` is accumulated
+    pub reward_per_share: u128, // accumulated rewards per pool token, scaled by `REWARD_PRECISION`
+    pub reward_per_share_paid: LookupMap<AccountId, u128>, // `reward_per_share` at the time an account's rewards were last settled
+    pub rewards: LookupMap<AccountId, Balance>, // settled rewards that haven't been claimed yet
+    pub undistributed: Balance, // rewards emitted while there was no pool token supply, can be swept by `gov`
+}
+
+impl RewardProgram {
+    /**
+     * @notice create a new reward program without any emissions, emissions are started through `start`
+     * @param pool_id is the id of the pool the program belongs to, used for unique storage pointers
+     * @param reward_token_id is the account id of the token that's streamed to LPs
+     * @returns a new `RewardProgram` instance
+     */
+    pub fn new(pool_id: u64, reward_token_id: AccountId) -> Self {
+        Self {
+            reward_token_id,
+            reward_rate: 0,
+            start_time: 0,
+            end_time: 0,
+            last_update_time: 0,
+            reward_per_share: 0,
+            reward_per_share_paid: LookupMap::new(format!("p{}rp", pool_id).as_bytes().to_vec()),
+            rewards: LookupMap::new(format!("p{}rw", pool_id).as_bytes().to_vec()),
+            undistributed: 0,
+        }
+    }
+
+    /**
+     * @param total_supply is the total supply of pool tokens since `last_update_time`
+     * @param now is the current time in ms
+     * @returns the reward per share accumulated up to `now`, rewards emitted while there's no pool token supply are added to `undistributed` instead
+     */
+    pub fn get_reward_per_share(&self, total_supply: Balance, now: Timestamp) -> u128 {
+        let applicable_time = std::cmp::min(now, self.end_time);
+        if total_supply == 0 || applicable_time <= self.last_update_time {
+            return self.reward_per_share;
+        }
+
+        let emitted = self.reward_rate * u128::from(applicable_time - self.last_update_time);
+        self.reward_per_share + math::simple_div_u128(constants::REWARD_PRECISION, emitted, total_supply)
+    }
+
+    /**
+     * @param account_id is the account to return the rewards of
+     * @param balance is the pool token balance `account_id` held since its rewards were last settled
+     * @param reward_per_share is the current reward per share
+     * @returns the rewards `account_id` can claim
+     */
+    pub fn get_earned(&self, account_id: &AccountId, balance: Balance, reward_per_share: u128) -> Balance {
+        let paid = self.reward_per_share_paid.get(account_id).unwrap_or(0);
+        let settled = self.rewards.get(account_id).unwrap_or(0);
+        settled + math::simple_mul_u128(constants::REWARD_PRECISION, balance, reward_per_share - paid)
+    }
+
+    /**
+     * @notice accumulates the rewards emitted since the last update, needs to be called before the pool token supply changes
+     * @param total_supply is the total supply of pool tokens since `last_update_time`
+     * @param now is the current time in ms
+     */
+    pub fn update(&mut self, total_supply: Balance, now: Timestamp) {
+        let applicable_time = std::cmp::min(now, self.end_time);
+        if total_supply == 0 && applicable_time > self.last_update_time {
+            self.undistributed += self.reward_rate * u128::from(applicable_time - self.last_update_time);
+        }
+
+        self.reward_per_share = self.get_reward_per_share(total_supply, now);
+        self.last_update_time = std::cmp::max(self.last_update_time, applicable_time);
+    }
+
+    /**
+     * @notice settles the rewards of an account, needs to be called after `update` and before its pool token balance changes
+     * @param account_id is the account to settle the rewards of
+     * @param balance is the pool token balance `account_id` held since its rewards were last settled
+     */
+    pub fn settle(&mut self, account_id: &AccountId, balance: Balance) {
+        let earned = self.get_earned(account_id, balance, self.reward_per_share);
+        self.rewards.insert(account_id, &earned);
+        self.reward_per_share_paid.insert(account_id, &self.reward_per_share);
+    }
+
+    /**
+     * @notice starts a new emission period, a program can only be restarted once the previous period ended
+     * @param total_supply is the current total supply of pool tokens
+     * @param now is the current time in ms
+     * @param reward_rate is the amount of reward tokens to distribute per ms
+     * @param duration is the duration of the emission period in ms
+     */
+    pub fn start(&mut self, total_supply: Balance, now: Timestamp, reward_rate: Balance, duration: u64) {
+        assert!(reward_rate > 0 && duration > 0, "ERR_INVALID_REWARD_PROGRAM");
+        assert!(now >= self.end_time, "ERR_REWARD_PROGRAM_ACTIVE");
+        self.update(total_supply, now);
+
+        self.reward_rate = reward_rate;
+        self.start_time = now;
+        self.end_time = now + duration;
+        self.last_update_time = now;
+    }
+
+    /**
+     * @notice settles and resets the rewards of an account so they can be paid out
+     * @param account_id is the account to claim the rewards for
+     * @param balance is the current pool token balance of `account_id`
+     * @param total_supply is the current total supply of pool tokens
+     * @param now is the current time in ms
+     * @returns the amount of reward tokens to pay out
+     */
+    pub fn claim(&mut self, account_id: &AccountId, balance: Balance, total_supply: Balance, now: Timestamp) -> Balance {
+        self.update(total_supply, now);
+        self.settle(account_id, balance);
+        let rewards = self.rewards.get(account_id).unwrap_or(0);
+        self.rewards.insert(account_id, &0);
+        rewards
+    }
+
+    /**
+     * @notice resets the rewards that were emitted while there was no pool token supply so they can be paid out to `gov`
+     * @param total_supply is the current total supply of pool tokens
+     * @param now is the current time in ms
+     * @returns the amount of reward tokens to pay out
+     */
+    pub fn take_undistributed(&mut self, total_supply: Balance, now: Timestamp) -> Balance {
+        self.update(total_supply, now);
+        let undistributed = self.undistributed;
+        self.undistributed = 0;
+        undistributed
+    }
+}
+
+/**
+ * @notice public representation of a `RewardProgram`
+ */
+#[derive(Serialize, Deserialize)]
+pub struct RewardProgramView {
+    pub reward_token_id: AccountId,
+    pub reward_rate: WrappedBalance,
+    pub start_time: WrappedTimestamp,
+    pub end_time: WrappedTimestamp,
+}
+
+#[near_bindgen]
+impl AMMContract {
+    /**
+     * @param market_id is the index of the market to return the reward program of
+     * @returns the market's reward program or `None` if it never had one
+     */
+    pub fn get_reward_program(&self, market_id: U64) -> Option<RewardProgramView> {
+        let market = self.markets.get(market_id.into()).expect("ERR_NO_MARKET");
+        market.pool.reward_program.map(|program| RewardProgramView {
+            reward_token_id: program.reward_token_id,
+            reward_rate: U128(program.reward_rate),
+            start_time: U64(program.start_time),
+            end_time: U64(program.end_time),
+        })
+    }
+
+    /**
+     * @param market_id is the index of the market to return the claimable rewards for
+     * @param account_id is the account to return the claimable rewards of
+     * @returns the wrapped amount of reward tokens `account_id` can claim
+     */
+    pub fn get_claimable_rewards(&self, market_id: U64, account_id: ValidAccountId) -> WrappedBalance {
+        let market = self.markets.get(market_id.into()).expect("ERR_NO_MARKET");
+        U128(market.pool.get_claimable_rewards(account_id.as_ref()))
+    }
+
+    /**
+     * @notice transfers the liquidity mining rewards the sender accrued as an LP
+     * @param market_id references the market to claim the rewards of
+     * @returns a promise referencing the reward token transaction
+     */
+    #[payable]
+    pub fn claim_rewards(
+        &mut self,
+        market_id: U64
+    ) -> Promise {
+        self.assert_unpaused();
+        let initial_storage = env::storage_usage();
+
+        let mut market = self.markets.get(market_id.into()).expect("ERR_NO_MARKET");
+        let (reward_token_id, rewards) = market.pool.claim_rewards(&env::predecessor_account_id());
+        assert!(rewards > 0, "ERR_NO_REWARDS");
+
+        self.markets.replace(market_id.into(), &market);
+        helper::refund_storage(initial_storage, env::predecessor_account_id());
+
+        logger::log_claimed_rewards(market_id, &env::predecessor_account_id(), &reward_token_id, rewards);
+
+        collateral_token::ft_transfer(
+            env::predecessor_account_id(),
+            U128(rewards),
+            None,
+            &reward_token_id,
+            1,
+            GAS_BASE_COMPUTE
+        )
+    }
+
+    /**
+     * @notice transfers the rewards that were emitted while the pool had no LPs back to `gov`, can only be called by `gov`
+     * @param market_id references the market to sweep the undistributed rewards of
+     * @returns a promise referencing the reward token transaction
+     */
+    pub fn sweep_undistributed_rewards(
+        &mut self,
+        market_id: U64
+    ) -> Promise {
+        self.assert_gov();
+
+        let mut market = self.markets.get(market_id.into()).expect("ERR_NO_MARKET");
+        let (reward_token_id, undistributed) = market.pool.take_undistributed_rewards();
+        assert!(undistributed > 0, "ERR_NO_UNDISTRIBUTED_REWARDS");
+        self.markets.replace(market_id.into(), &market);
+
+        logger::log_swept_rewards(market_id, &reward_token_id, undistributed);
+
+        collateral_token::ft_transfer(
+            self.gov.to_string(),
+            U128(undistributed),
+            None,
+            &reward_token_id,
+            1,
+            GAS_BASE_COMPUTE
+        )
+    }
+}
+
+impl AMMContract {
+    /**
+     * @notice starts an emission period of the market's reward program, only callable by `gov` through `ft_transfer_call` of the reward token
+     * @param sender the sender of the original transfer_call
+     * @param amount is the amount of reward tokens transferred, needs to cover `reward_rate * duration`
+     * @param args is the `FundRewardProgram` args
+     * @returns the amount of reward tokens that aren't needed for the emission period and are refunded
+     */
+    pub fn fund_reward_program(
+        &mut self,
+        sender: &AccountId,
+        amount: u128,
+        args: FundRewardProgramArgs
+    ) -> PromiseOrValue<U128> {
+        assert_eq!(sender, &self.gov, "ERR_NO_GOVERNANCE_ADDRESS");
+        let mut market = self.markets.get(args.market_id.into()).expect("ERR_NO_MARKET");
+        assert!(market.enabled, "ERR_DISABLED_MARKET");
+        assert!(!market.finalized, "ERR_FINALIZED_MARKET");
+
+        let reward_rate: u128 = args.reward_rate.into();
+        let duration: u64 = args.duration.into();
+        let required = reward_rate * u128::from(duration);
+        assert!(amount >= required, "ERR_INSUFFICIENT_REWARD_AMOUNT");
+
+        market.pool.fund_reward_program(env::predecessor_account_id(), reward_rate, duration);
+        self.markets.replace(args.market_id.into(), &market);
+
+        logger::log_reward_program(args.market_id, market.pool.reward_program.as_ref().unwrap());
+
+        PromiseOrValue::Value(U128(amount - required))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod reward_program_tests {
+    use super::*;
+    use near_sdk::{ MockedBlockchain };
+    use near_sdk::{ testing_env, VMContext };
+
+    fn alice() -> AccountId {
+        "alice.near".to_string()
+    }
+
+    fn bob() -> AccountId {
+        "bob.near".to_string()
+    }
+
+    fn token() -> AccountId {
+        "token.near".to_string()
+    }
+
+    fn get_context() -> VMContext {
+        VMContext {
+            current_account_id: alice(),
+            signer_account_id: alice(),
+            signer_account_pk: vec![0, 1, 2],
+            predecessor_account_id: alice(),
+            input: vec![],
+            block_index: 0,
+            block_timestamp: 0,
+            account_balance: 1000 * 10u128.pow(24),
+            account_locked_balance: 0,
+            storage_usage: 10u64.pow(6),
+            attached_deposit: 0,
+            prepaid_gas: 10u64.pow(18),
+            random_seed: vec![0, 1, 2],
+            is_view: false,
+            output_data_receivers: vec![],
+            epoch_height: 0,
+        }
+    }
+
+    #[test]
+    fn rewards_accrue_pro_rata() {
+        testing_env!(get_context());
+        let mut program = RewardProgram::new(0, token());
+        let rate = 10_u128.pow(18);
+
+        // Alice is the only LP for the first 100ms
+        program.start(100, 0, rate, 1000);
+        program.update(100, 100);
+        program.settle(&bob(), 0);
+
+        // Bob joins with the same amount of pool tokens
+        let reward_per_share = program.get_reward_per_share(200, 300);
+        assert_eq!(program.get_earned(&alice(), 100, reward_per_share), 200 * rate);
+        assert_eq!(program.get_earned(&bob(), 100, reward_per_share), 100 * rate);
+    }
+
+    #[test]
+    fn rewards_stop_at_end_time() {
+        testing_env!(get_context());
+        let mut program = RewardProgram::new(0, token());
+        let rate = 10_u128.pow(18);
+
+        program.start(100, 0, rate, 1000);
+        assert_eq!(program.claim(&alice(), 100, 100, 5000), 1000 * rate);
+        assert_eq!(program.claim(&alice(), 100, 100, 6000), 0);
+
+        // A new emission period continues on the same accumulator
+        program.start(100, 6000, rate, 1000);
+        assert_eq!(program.claim(&alice(), 100, 100, 6500), 500 * rate);
+    }
+
+    #[test]
+    fn rewards_without_supply_are_undistributed() {
+        testing_env!(get_context());
+        let mut program = RewardProgram::new(0, token());
+        let rate = 10_u128.pow(18);
+
+        // Nobody provides liquidity for the first 300ms
+        program.start(0, 0, rate, 1000);
+        program.update(0, 300);
+        program.settle(&alice(), 0);
+
+        // Alice is the only LP until she exits at 500ms
+        program.update(100, 500);
+        assert_eq!(program.claim(&alice(), 100, 100, 500), 200 * rate);
+
+        // Rewards emitted after every LP exited are undistributed as well
+        assert_eq!(program.take_undistributed(0, 800), 600 * rate);
+        assert_eq!(program.take_undistributed(0, 2000), 200 * rate);
+        assert_eq!(program.take_undistributed(0, 3000), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_REWARD_PROGRAM_ACTIVE")]
+    fn restart_active_program() {
+        testing_env!(get_context());
+        let mut program = RewardProgram::new(0, token());
+        program.start(100, 0, 1, 1000);
+        program.start(100, 500, 1, 1000);
+    }
+}
@@ -39,14 +39,6 @@ pub trait StorageManager {
     fn storage_balance_of(&self, account_id: ValidAccountId) -> Option<StorageBalance>;
 }
 
-fn assert_one_yocto() {
-    assert_eq!(
-        env::attached_deposit(),
-        1,
-        "Requires attached deposit of exactly 1 yoctoNEAR"
-    )
-}
-
 #[near_bindgen]
 impl StorageManager for AMMContract {
 
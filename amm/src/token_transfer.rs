@@ -0,0 +1,349 @@
+use crate::*;
+use near_sdk::PromiseResult;
+
+const GAS_FOR_RESOLVE_TRANSFER: Gas = 10_000_000_000_000;
+const GAS_FOR_TRANSFER_CALL: Gas = 25_000_000_000_000 + GAS_FOR_RESOLVE_TRANSFER;
+
+#[ext_contract(ext_outcome_token_receiver)]
+pub trait OutcomeTokenReceiver {
+    // @returns amount of unused tokens
+    fn outcome_ft_on_transfer(&mut self, sender_id: AccountId, market_id: U64, outcome: u16, amount: WrappedBalance, msg: String) -> PromiseOrValue<U128>;
+}
+
+#[ext_contract(ext_lp_token_receiver)]
+pub trait LpTokenReceiver {
+    // @returns amount of unused tokens
+    fn lp_on_transfer(&mut self, sender_id: AccountId, market_id: U64, amount: WrappedBalance, msg: String) -> PromiseOrValue<U128>;
+}
+
+#[ext_contract(ext_transfer_resolver)]
+trait TransferResolver {
+    fn outcome_ft_resolve_transfer(&mut self, sender_id: AccountId, receiver_id: AccountId, market_id: U64, outcome: u16, amount: WrappedBalance) -> U128;
+    fn lp_resolve_transfer(&mut self, sender_id: AccountId, receiver_id: AccountId, market_id: U64, amount: WrappedBalance) -> U128;
+}
+
+#[near_bindgen]
+impl AMMContract {
+    /**
+     * @param market_id is the index of the market the outcome token belongs to
+     * @param outcome is the outcome token to get the balance of
+     * @param account_id is the `AccountId` to retrieve the balance for
+     * @returns wrapped balance of `outcome_shares`
+     */
+    pub fn outcome_ft_balance_of(
+        &self,
+        market_id: U64,
+        outcome: u16,
+        account_id: ValidAccountId
+    ) -> WrappedBalance {
+        let market = self.get_market_expect(market_id);
+        U128(market.pool.get_share_balance(account_id.as_ref(), outcome))
+    }
+
+    /**
+     * @notice transfers `outcome_shares` from the sender to `receiver_id`, the sender's `spent` on the transferred shares moves along
+     * @param market_id references the market the shares belong to
+     * @param outcome is the outcome of the shares to transfer
+     * @param receiver_id is the account that should receive the shares
+     * @param amount is the amount of shares to transfer
+     * @param memo is an optional message for the receiver
+     */
+    #[payable]
+    pub fn outcome_ft_transfer(
+        &mut self,
+        market_id: U64,
+        outcome: u16,
+        receiver_id: ValidAccountId,
+        amount: WrappedBalance,
+        memo: Option<String>
+    ) {
+        self.assert_unpaused();
+        assert_one_yocto();
+        let initial_storage = env::storage_usage();
+        let sender_id = env::predecessor_account_id();
+        let account = self.get_storage_account(&sender_id);
+
+        self.internal_outcome_transfer(market_id, outcome, &sender_id, receiver_id.as_ref(), amount.into(), memo, true);
+
+        self.use_storage(&sender_id, initial_storage, account.available);
+    }
+
+    /**
+     * @notice transfers `outcome_shares` from the sender to `receiver_id` and calls `outcome_ft_on_transfer` on the receiver
+     *         any shares the receiver returns as unused are transferred back to the sender in `outcome_ft_resolve_transfer`
+     * @param market_id references the market the shares belong to
+     * @param outcome is the outcome of the shares to transfer
+     * @param receiver_id is the contract that should receive the shares
+     * @param amount is the amount of shares to transfer
+     * @param memo is an optional message for the receiver
+     * @param msg is passed on to the receiver
+     * @returns a promise that resolves to the amount of shares used by the receiver
+     */
+    #[payable]
+    pub fn outcome_ft_transfer_call(
+        &mut self,
+        market_id: U64,
+        outcome: u16,
+        receiver_id: ValidAccountId,
+        amount: WrappedBalance,
+        memo: Option<String>,
+        msg: String
+    ) -> Promise {
+        self.assert_unpaused();
+        assert_one_yocto();
+        assert!(env::prepaid_gas() > GAS_FOR_TRANSFER_CALL, "ERR_MORE_GAS_REQUIRED");
+        let initial_storage = env::storage_usage();
+        let sender_id = env::predecessor_account_id();
+        let account = self.get_storage_account(&sender_id);
+        let receiver_id: AccountId = receiver_id.into();
+
+        self.internal_outcome_transfer(market_id, outcome, &sender_id, &receiver_id, amount.into(), memo, true);
+
+        self.use_storage(&sender_id, initial_storage, account.available);
+
+        ext_outcome_token_receiver::outcome_ft_on_transfer(
+            sender_id.to_string(),
+            market_id,
+            outcome,
+            amount,
+            msg,
+            &receiver_id,
+            0,
+            env::prepaid_gas() - GAS_FOR_TRANSFER_CALL
+        ).then(ext_transfer_resolver::outcome_ft_resolve_transfer(
+            sender_id,
+            receiver_id.to_string(),
+            market_id,
+            outcome,
+            amount,
+            &env::current_account_id(),
+            0,
+            GAS_FOR_RESOLVE_TRANSFER
+        ))
+    }
+
+    /**
+     * @notice resolves an `outcome_ft_transfer_call` by returning the shares the receiver didn't use to the sender
+     * @returns the amount of shares that were used by the receiver
+     */
+    pub fn outcome_ft_resolve_transfer(
+        &mut self,
+        sender_id: AccountId,
+        receiver_id: AccountId,
+        market_id: U64,
+        outcome: u16,
+        amount: WrappedBalance
+    ) -> U128 {
+        assert_self();
+        let amount: u128 = amount.into();
+
+        let unused_amount = match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(value) => {
+                match serde_json::from_slice::<U128>(&value) {
+                    Ok(unused_amount) => std::cmp::min(amount, unused_amount.into()),
+                    Err(_e) => amount,
+                }
+            },
+            PromiseResult::Failed => amount,
+        };
+
+        if unused_amount > 0 {
+            let market = self.get_market_expect(market_id);
+            let receiver_balance = market.pool.get_share_balance(&receiver_id, outcome);
+            let refund_amount = std::cmp::min(unused_amount, receiver_balance);
+
+            if refund_amount > 0 {
+                // Refunds have to go through even if the market was disabled while the receiver was called
+                self.internal_outcome_transfer(market_id, outcome, &receiver_id, &sender_id, refund_amount, None, false);
+                return U128(amount - refund_amount);
+            }
+        }
+
+        U128(amount)
+    }
+
+    /**
+     * @notice transfers LP tokens from the sender to `receiver_id`, the sender's accrued fees are paid out and their `lp_spent` on the transferred LP tokens moves along
+     * @param market_id references the market the LP tokens belong to
+     * @param receiver_id is the account that should receive the LP tokens
+     * @param amount is the amount of LP tokens to transfer
+     * @returns a promise that resolves to whether the sender's fees were transferred, or `true` if the sender had no fees to pay out
+     */
+    #[payable]
+    pub fn lp_transfer(
+        &mut self,
+        market_id: U64,
+        receiver_id: ValidAccountId,
+        amount: WrappedBalance
+    ) -> PromiseOrValue<bool> {
+        self.assert_unpaused();
+        assert_one_yocto();
+        let initial_storage = env::storage_usage();
+        let sender_id = env::predecessor_account_id();
+        let account = self.get_storage_account(&sender_id);
+
+        let fees_promise = self.internal_lp_transfer(market_id, &sender_id, receiver_id.as_ref(), amount.into(), None, true);
+
+        self.use_storage(&sender_id, initial_storage, account.available);
+
+        match fees_promise {
+            Some(promise) => PromiseOrValue::Promise(promise),
+            None => PromiseOrValue::Value(true)
+        }
+    }
+
+    /**
+     * @notice transfers LP tokens from the sender to `receiver_id` and calls `lp_on_transfer` on the receiver
+     *         any LP tokens the receiver returns as unused are transferred back to the sender in `lp_resolve_transfer`
+     * @param market_id references the market the LP tokens belong to
+     * @param receiver_id is the contract that should receive the LP tokens
+     * @param amount is the amount of LP tokens to transfer
+     * @param msg is passed on to the receiver
+     * @returns a promise that resolves to the amount of LP tokens used by the receiver
+     */
+    #[payable]
+    pub fn lp_transfer_call(
+        &mut self,
+        market_id: U64,
+        receiver_id: ValidAccountId,
+        amount: WrappedBalance,
+        msg: String
+    ) -> Promise {
+        self.assert_unpaused();
+        assert_one_yocto();
+        assert!(env::prepaid_gas() > GAS_FOR_TRANSFER_CALL + GAS_FOR_FEES_TRANSFER, "ERR_MORE_GAS_REQUIRED");
+        let initial_storage = env::storage_usage();
+        let sender_id = env::predecessor_account_id();
+        let account = self.get_storage_account(&sender_id);
+        let receiver_id: AccountId = receiver_id.into();
+
+        // Fees are paid out in a separate promise that doesn't influence the transfer call, they're re-credited if that transfer fails
+        self.internal_lp_transfer(market_id, &sender_id, &receiver_id, amount.into(), None, true);
+
+        self.use_storage(&sender_id, initial_storage, account.available);
+
+        ext_lp_token_receiver::lp_on_transfer(
+            sender_id.to_string(),
+            market_id,
+            amount,
+            msg,
+            &receiver_id,
+            0,
+            env::prepaid_gas() - GAS_FOR_TRANSFER_CALL - GAS_FOR_FEES_TRANSFER
+        ).then(ext_transfer_resolver::lp_resolve_transfer(
+            sender_id,
+            receiver_id.to_string(),
+            market_id,
+            amount,
+            &env::current_account_id(),
+            0,
+            GAS_FOR_RESOLVE_TRANSFER
+        ))
+    }
+
+    /**
+     * @notice resolves an `lp_transfer_call` by returning the LP tokens the receiver didn't use to the sender
+     * @returns the amount of LP tokens that were used by the receiver
+     */
+    pub fn lp_resolve_transfer(
+        &mut self,
+        sender_id: AccountId,
+        receiver_id: AccountId,
+        market_id: U64,
+        amount: WrappedBalance
+    ) -> U128 {
+        assert_self();
+        let amount: u128 = amount.into();
+
+        let unused_amount = match env::promise_result(0) {
+            PromiseResult::NotReady => unreachable!(),
+            PromiseResult::Successful(value) => {
+                match serde_json::from_slice::<U128>(&value) {
+                    Ok(unused_amount) => std::cmp::min(amount, unused_amount.into()),
+                    Err(_e) => amount,
+                }
+            },
+            PromiseResult::Failed => amount,
+        };
+
+        if unused_amount > 0 {
+            let market = self.get_market_expect(market_id);
+            let receiver_balance = market.pool.get_pool_token_balance(&receiver_id);
+            let refund_amount = std::cmp::min(unused_amount, receiver_balance);
+
+            if refund_amount > 0 {
+                // Refunds have to go through even if the market was disabled while the receiver was called
+                self.internal_lp_transfer(market_id, &receiver_id, &sender_id, refund_amount, None, false);
+                return U128(amount - refund_amount);
+            }
+        }
+
+        U128(amount)
+    }
+}
+
+impl AMMContract {
+    /**
+     * @notice transfers `outcome_shares` between two accounts within a market
+     * @param market_id references the market the shares belong to
+     * @param outcome is the outcome of the shares to transfer
+     * @param sender_id is the account that's sending the shares
+     * @param receiver_id is the account that should receive the shares
+     * @param amount is the amount of shares to transfer
+     * @param memo is an optional message that's logged with the transfer
+     * @param assert_enabled is whether the transfer should fail if the market is disabled, `false` for refunds of a transfer call
+     */
+    pub fn internal_outcome_transfer(
+        &mut self,
+        market_id: U64,
+        outcome: u16,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        amount: u128,
+        memo: Option<String>,
+        assert_enabled: bool
+    ) {
+        let mut market = self.markets.get(market_id.into()).expect("ERR_NO_MARKET");
+        assert!(!assert_enabled || market.enabled, "ERR_DISABLED_MARKET");
+
+        market.pool.transfer_outcome_tokens(sender_id, receiver_id, outcome, amount);
+        self.markets.replace(market_id.into(), &market);
+
+        logger::log_mt_transfer(sender_id, receiver_id, &[multi_token::outcome_token_id(market_id.into(), outcome)], &[amount], memo);
+    }
+
+    /**
+     * @notice transfers LP tokens between two accounts within a market and pays out the fees the sender accrued
+     * @param market_id references the market the LP tokens belong to
+     * @param sender_id is the account that's sending the LP tokens
+     * @param receiver_id is the account that should receive the LP tokens
+     * @param amount is the amount of LP tokens to transfer
+     * @param memo is an optional message that's logged with the transfer
+     * @param assert_enabled is whether the transfer should fail if the market is disabled, `false` for refunds of a transfer call
+     * @returns an optional `Promise` of the fee transfer to `sender_id`, `None` if no fees were accrued
+     */
+    pub fn internal_lp_transfer(
+        &mut self,
+        market_id: U64,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        amount: u128,
+        memo: Option<String>,
+        assert_enabled: bool
+    ) -> Option<Promise> {
+        let mut market = self.markets.get(market_id.into()).expect("ERR_NO_MARKET");
+        assert!(!assert_enabled || market.enabled, "ERR_DISABLED_MARKET");
+
+        let fees = market.pool.transfer_pool_tokens(sender_id, receiver_id, amount);
+        self.markets.replace(market_id.into(), &market);
+
+        logger::log_mt_transfer(sender_id, receiver_id, &[multi_token::lp_token_id(market_id.into())], &[amount], memo);
+
+        if fees > 0 {
+            Some(self.transfer_lp_fees(sender_id, &market.pool.collateral_token_id, vec![market_id], vec![U128(fees)]))
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use crate::utils::*;
+use near_sdk::json_types::{U128};
+use near_sdk_sim::{to_yocto};
+
+#[test]
+fn complete_set_mint_merge_test() {
+    let test_utils = TestUtils::init(carol());
+
+    let market_id = 0;
+    let seed_amount = to_yocto("10");
+    let mint_amount = to_yocto("3");
+    let weights = Some(calc_weights_from_price(vec![to_yocto("6") / 10, to_yocto("3") / 10, to_yocto("1") / 10]));
+
+    test_utils.alice.create_market(3, Some(fee()));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+    let pool_balances = test_utils.alice.get_pool_balances(market_id);
+
+    let init_balance_bob = test_utils.bob.get_token_balance(None);
+    test_utils.bob.mint_complete_set(market_id, mint_amount);
+
+    assert_eq!(test_utils.bob.get_token_balance(None), init_balance_bob - mint_amount);
+    for outcome in 0..3 {
+        assert_eq!(test_utils.bob.get_outcome_balance(None, market_id, outcome), mint_amount);
+    }
+    // The pool isn't touched
+    assert_eq!(test_utils.alice.get_pool_balances(market_id), pool_balances);
+
+    test_utils.bob.merge_complete_set(market_id, mint_amount);
+
+    assert_eq!(test_utils.bob.get_token_balance(None), init_balance_bob);
+    for outcome in 0..3 {
+        assert_eq!(test_utils.bob.get_outcome_balance(None, market_id, outcome), 0);
+    }
+}
+
+#[test]
+fn complete_set_invalid_refund_test() {
+    let test_utils = TestUtils::init(carol());
+
+    let market_id = 0;
+    let seed_amount = to_yocto("10");
+    let mint_amount = to_yocto("3");
+    let half = to_yocto("5") / 10;
+    let weights = Some(vec![U128(half), U128(half)]);
+
+    test_utils.alice.create_market(2, Some(U128(0)));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    let init_balance_bob = test_utils.bob.get_token_balance(None);
+    test_utils.bob.mint_complete_set(market_id, mint_amount);
+
+    // Sell part of outcome 0 so the merged shares no longer cost exactly 1 each
+    test_utils.bob.sell(market_id, to_yocto("1"), 0, mint_amount);
+    let balance_bob_after_sell = test_utils.bob.get_token_balance(None);
+    let shares_left = test_utils.bob.get_outcome_balance(None, market_id, 0);
+    test_utils.bob.merge_complete_set(market_id, shares_left);
+
+    test_utils.alice.exit_liquidity(market_id, seed_amount);
+    test_utils.carol.resolute_market(market_id, None);
+    test_utils.bob.claim_earnings(market_id);
+    test_utils.alice.claim_earnings(market_id);
+
+    // Without fees bob gets his full collateral back on an invalid market
+    let final_balance_bob = test_utils.bob.get_token_balance(None);
+    assert!(final_balance_bob > balance_bob_after_sell);
+    assert!(std::cmp::max(init_balance_bob, final_balance_bob) - std::cmp::min(init_balance_bob, final_balance_bob) < 10);
+}
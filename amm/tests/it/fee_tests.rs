@@ -1,5 +1,5 @@
 use crate::utils::*;
-use near_sdk::json_types::{U128};
+use near_sdk::json_types::{U128, U64};
 use near_sdk_sim::{to_yocto};
 
 #[test]
@@ -150,4 +150,159 @@ fn test_specific_fee_scenario() {
     assert_eq!(bob_bal, expected_bob_balance);
     assert_eq!(carol_bal, expected_carol_balance);
     assert_eq!(alice_bal, expected_alice_balance);
-}
\ No newline at end of file
+}
+
+#[test]
+fn fee_protocol_share_test() {
+    let test_utils = TestUtils::init(carol());
+    
+    // variables
+    let market_id = 0;
+
+    let target_price = to_yocto("5") / 10;
+    let seed_amount = to_yocto("100");
+    let buy_amount = to_yocto("1");
+    let weights = Some(calc_weights_from_price(vec![target_price, target_price]));
+    let swap_fee = to_yocto("2") / 100;
+    test_utils.carol.set_protocol_fee_share(2_500); // 25%
+    test_utils.alice.create_market(2, Some(U128(swap_fee)));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    for _ in 0..5 {
+        test_utils.bob.buy(market_id, buy_amount, 0, 0);
+        test_utils.bob.buy(market_id, buy_amount, 1, 0);
+    }
+
+    // 10 buys of 1 pay 0.2 in fees, a quarter of which goes to the protocol
+    let expected_protocol_fees = to_yocto("5") / 100;
+    let expected_claimable_by_alice = to_yocto("15") / 100;
+    assert_eq!(test_utils.alice.get_fees_withdrawable(market_id, None), expected_claimable_by_alice);
+    assert_eq!(test_utils.carol.get_protocol_fees(TOKEN_CONTRACT_ID), expected_protocol_fees);
+
+    // Protocol fees are withdrawn to the treasury, which defaults to gov
+    let carol_init_balance = test_utils.carol.get_token_balance(None);
+    test_utils.carol.withdraw_protocol_fees(TOKEN_CONTRACT_ID);
+    assert_eq!(test_utils.carol.get_token_balance(None), carol_init_balance + expected_protocol_fees);
+    assert_eq!(test_utils.carol.get_protocol_fees(TOKEN_CONTRACT_ID), 0);
+}
+
+#[test]
+fn fee_creator_share_test() {
+    let test_utils = TestUtils::init(carol());
+    
+    // variables
+    let market_id = 1;
+
+    let target_price = to_yocto("5") / 10;
+    let seed_amount = to_yocto("100");
+    let buy_amount = to_yocto("1");
+    let weights = Some(calc_weights_from_price(vec![target_price, target_price]));
+    let swap_fee = to_yocto("2") / 100;
+    test_utils.bob.create_market(2, Some(U128(swap_fee)));
+    test_utils.alice.create_market_with_creator_fee(2, Some(U128(swap_fee)), 5_000); // 50%
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    assert_eq!(test_utils.alice.get_markets_by_creator(&test_utils.alice.account.account_id()), vec![U64(market_id)]);
+    assert_eq!(test_utils.alice.get_markets_by_creator(&test_utils.bob.account.account_id()), vec![U64(0)]);
+
+    for _ in 0..5 {
+        test_utils.bob.buy(market_id, buy_amount, 0, 0);
+        test_utils.bob.buy(market_id, buy_amount, 1, 0);
+    }
+
+    // 10 buys of 1 pay 0.2 in fees, half of which goes to the creator
+    let expected_creator_fees = to_yocto("1") / 10;
+    assert_eq!(test_utils.alice.get_fees_withdrawable(market_id, None), to_yocto("1") / 10);
+    assert_eq!(test_utils.alice.get_creator_fees(market_id), expected_creator_fees);
+
+    let alice_init_balance = test_utils.alice.get_token_balance(None);
+    test_utils.alice.claim_creator_fees(market_id);
+    assert_eq!(test_utils.alice.get_token_balance(None), alice_init_balance + expected_creator_fees);
+    assert_eq!(test_utils.alice.get_creator_fees(market_id), 0);
+}
+
+#[test]
+fn fee_claim_lp_fees_test() {
+    let test_utils = TestUtils::init(carol());
+    
+    // variables
+    let market_id = 0;
+
+    let target_price = to_yocto("5") / 10;
+    let seed_amount = to_yocto("100");
+    let buy_amount = to_yocto("1");
+    let weights = Some(calc_weights_from_price(vec![target_price, target_price]));
+    let swap_fee = to_yocto("2") / 100;
+    test_utils.alice.create_market(2, Some(U128(swap_fee)));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    test_utils.bob.buy(market_id, buy_amount, 0, 0);
+    test_utils.bob.buy(market_id, buy_amount, 1, 0);
+
+    let alice_init_balance = test_utils.alice.get_token_balance(None);
+    let pool_token_balance = test_utils.alice.get_pool_token_balance(market_id, None);
+    test_utils.alice.claim_lp_fees(market_id);
+
+    // Fees are paid out while the LP position stays the same
+    assert_eq!(test_utils.alice.get_token_balance(None), alice_init_balance + to_yocto("4") / 100);
+    assert_eq!(test_utils.alice.get_pool_token_balance(market_id, None), pool_token_balance);
+    assert_eq!(test_utils.alice.get_fees_withdrawable(market_id, None), 0);
+
+    // Only fees accrued after the claim are paid out on exit
+    test_utils.bob.buy(market_id, buy_amount, 0, 0);
+    assert_eq!(test_utils.alice.get_fees_withdrawable(market_id, None), to_yocto("2") / 100);
+}
+
+#[test]
+fn fee_claim_lp_fees_batch_test() {
+    let test_utils = TestUtils::init(carol());
+
+    let target_price = to_yocto("5") / 10;
+    let seed_amount = to_yocto("100");
+    let buy_amount = to_yocto("1");
+    let swap_fee = to_yocto("2") / 100;
+
+    for market_id in 0..2 {
+        let weights = Some(calc_weights_from_price(vec![target_price, target_price]));
+        test_utils.alice.create_market(2, Some(U128(swap_fee)));
+        test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+        test_utils.bob.buy(market_id, buy_amount, 0, 0);
+    }
+
+    let alice_init_balance = test_utils.alice.get_token_balance(None);
+    test_utils.alice.claim_lp_fees_batch(vec![0, 1]);
+
+    assert_eq!(test_utils.alice.get_token_balance(None), alice_init_balance + to_yocto("4") / 100);
+    assert_eq!(test_utils.alice.get_fees_withdrawable(0, None), 0);
+    assert_eq!(test_utils.alice.get_fees_withdrawable(1, None), 0);
+}
+
+#[test]
+fn fee_claim_lp_fees_failed_transfer_test() {
+    let test_utils = TestUtils::init(carol());
+
+    let market_id = 0;
+    let target_price = to_yocto("5") / 10;
+    let seed_amount = to_yocto("100");
+    let buy_amount = to_yocto("1");
+    let weights = Some(calc_weights_from_price(vec![target_price, target_price]));
+    let swap_fee = to_yocto("2") / 100;
+    test_utils.alice.create_market(2, Some(U128(swap_fee)));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    // Dave isn't registered with the collateral token so every transfer to him fails
+    let dave = test_utils.master_account.account.create_user("dave".to_string(), init_balance());
+    storage_deposit(AMM_CONTRACT_ID, &test_utils.master_account.account, SAFE_STORAGE_AMOUNT, Some(dave.account_id()));
+    let dave = TestAccount { account: dave };
+    test_utils.alice.lp_transfer(market_id, dave.account.account_id(), seed_amount / 2);
+
+    test_utils.bob.buy(market_id, buy_amount, 0, 0);
+    let dave_fees = dave.get_fees_withdrawable(market_id, None);
+    assert!(dave_fees > 0);
+
+    dave.claim_lp_fees(market_id);
+
+    // The fees that failed to transfer can be claimed again
+    assert_eq!(dave.get_fees_withdrawable(market_id, None), dave_fees);
+    assert_eq!(dave.get_pool_token_balance(market_id, None), seed_amount / 2);
+}
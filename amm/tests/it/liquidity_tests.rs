@@ -217,4 +217,97 @@ fn liquidity_exit_after_swap() {
     test_utils.alice.buy(market_id, buy_amount, 0, 0);
     let pool_token_balance = test_utils.alice.get_pool_token_balance(market_id, None);
     test_utils.alice.exit_liquidity(market_id, pool_token_balance);
-}
\ No newline at end of file
+}
+
+#[test]
+fn add_liquidity_zap_uneven_liq_test() {
+    let test_utils = TestUtils::init(carol());
+    
+    // variables
+    let market_id = 0;
+    let precision = to_yocto("1") / 1000;
+    
+    let target_price_a = to_yocto("60") / 100;
+    let target_price_b_c = to_yocto("20") / 100;
+    let target_prices = vec![target_price_a, target_price_b_c, target_price_b_c];
+    let weights = Some(calc_weights_from_price(target_prices));
+    let seed_amount = to_yocto("100");
+    let zap_amount = to_yocto("10");
+    
+    test_utils.alice.create_market(3, Some(U128(to_yocto("2") / 100)));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    let joiner_init_balance = test_utils.bob.get_token_balance(None);
+    test_utils.bob.add_liquidity_zap(market_id, zap_amount, None);
+
+    // The joiner only holds pool tokens, apart from dust left by rounding
+    assert!(test_utils.bob.get_pool_token_balance(market_id, None) > 0);
+    for outcome in 0..3 {
+        assert!(test_utils.bob.get_outcome_balance(None, market_id, outcome) < precision);
+    }
+
+    // Collateral that couldn't be added is refunded
+    let spent = joiner_init_balance - test_utils.bob.get_token_balance(None);
+    assert!(spent > 0 && spent <= zap_amount);
+
+    // Selling the leftover outcome tokens moves the prices towards even odds, but by less than the max deviation
+    let max_deviation = to_yocto("10") / 100;
+    let price_a = test_utils.bob.get_spot_price_sans_fee(market_id, 0);
+    let price_b = test_utils.bob.get_spot_price_sans_fee(market_id, 1);
+    assert!(price_a < target_price_a && target_price_a - price_a <= max_deviation);
+    assert!(price_b > target_price_b_c && price_b - target_price_b_c <= max_deviation);
+}
+
+#[test]
+fn add_liquidity_zap_price_deviation_test() {
+    let test_utils = TestUtils::init(carol());
+
+    // variables
+    let market_id = 0;
+    let target_price_a = to_yocto("60") / 100;
+    let target_price_b_c = to_yocto("20") / 100;
+    let target_prices = vec![target_price_a, target_price_b_c, target_price_b_c];
+    let weights = Some(calc_weights_from_price(target_prices));
+    let seed_amount = to_yocto("100");
+
+    test_utils.alice.create_market(3, Some(U128(to_yocto("2") / 100)));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    // Zapping as much as the pool holds would move the prices too far, the transfer is refunded
+    let joiner_init_balance = test_utils.bob.get_token_balance(None);
+    test_utils.bob.add_liquidity_zap(market_id, seed_amount, None);
+
+    assert_eq!(test_utils.bob.get_pool_token_balance(market_id, None), 0);
+    assert_eq!(test_utils.bob.get_token_balance(None), joiner_init_balance);
+    assert_eq!(test_utils.bob.get_spot_price_sans_fee(market_id, 0), target_price_a);
+}
+
+#[test]
+fn exit_pool_to_collateral_test() {
+    let test_utils = TestUtils::init(carol());
+
+    // variables
+    let market_id = 0;
+    let precision = to_yocto("1") / 1000;
+    let seed_amount = to_yocto("100");
+    let buy_amount = to_yocto("10");
+    let half = U128(to_yocto("5") / 10);
+    let weights = Some(vec![half, half]);
+
+    test_utils.alice.create_market(2, Some(U128(to_yocto("2") / 100)));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+    test_utils.bob.add_liquidity(market_id, seed_amount, None);
+    test_utils.carol.buy(market_id, buy_amount, 0, 0);
+
+    let joiner_init_balance = test_utils.bob.get_token_balance(None);
+    let pool_token_balance = test_utils.bob.get_pool_token_balance(market_id, None);
+    test_utils.bob.exit_pool_to_collateral(market_id, pool_token_balance, 0);
+
+    // The joiner is left with collateral only, apart from dust left by rounding
+    assert_eq!(test_utils.bob.get_pool_token_balance(market_id, None), 0);
+    for outcome in 0..2 {
+        assert!(test_utils.bob.get_outcome_balance(None, market_id, outcome) < precision);
+    }
+    let collateral_out = test_utils.bob.get_token_balance(None) - joiner_init_balance;
+    assert!(collateral_out > seed_amount - buy_amount / 10);
+}
@@ -10,3 +10,6 @@ mod uneven_lp_shares_solvency_tests;
 mod swap_tests;
 mod market_end_tests;
 mod fee_tests;
+mod transfer_tests;
+mod complete_set_tests;
+mod reward_tests;
@@ -147,4 +147,77 @@ fn payout_lp_no_exit() {
     assert_eq!(bob_final_balance, expected_bob_final_balance);
     assert_eq!(amm_final_balance, expected_amm_final_balance);
     
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_cancelled_market_payout() {
+    let test_utils = TestUtils::init(carol());
+    
+    // variables
+    let market_id = 0;
+    let creation_bond = 100;
+    let alice_init_balance: u128 = test_utils.alice.get_token_balance(None);
+    let bob_init_balance: u128 = test_utils.bob.get_token_balance(None);
+    
+    let target_price = to_yocto("5") / 10;
+    let seed_amount = to_yocto("100");
+    let buy_amount = to_yocto("1");
+    let weights = Some(calc_weights_from_price(vec![target_price, target_price]));
+
+    test_utils.alice.create_market(2, Some(U128(0)));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    test_utils.bob.buy(market_id, buy_amount, 0, 0);
+    test_utils.bob.buy(market_id, buy_amount, 1, 0);
+
+    test_utils.carol.cancel_market(market_id, "malicious market");
+
+    // Every participant reclaims their basis through the invalid payout path
+    test_utils.alice.exit_liquidity(market_id, seed_amount);
+    test_utils.bob.claim_earnings(market_id);
+    test_utils.alice.claim_earnings(market_id);
+    
+    assert_eq!(test_utils.alice.get_token_balance(None), alice_init_balance - creation_bond);
+    assert_eq!(test_utils.bob.get_token_balance(None), bob_init_balance);
+    assert_eq!(test_utils.bob.get_token_balance(Some(AMM_CONTRACT_ID.to_string())), 0);
+}
+
+#[test]
+fn test_cancelled_market_rejects_trading() {
+    let test_utils = TestUtils::init(carol());
+
+    // variables
+    let market_id = 0;
+    let creation_bond = 100;
+    let alice_init_balance: u128 = test_utils.alice.get_token_balance(None);
+    let bob_init_balance: u128 = test_utils.bob.get_token_balance(None);
+
+    let target_price = to_yocto("5") / 10;
+    let seed_amount = to_yocto("100");
+    let buy_amount = to_yocto("1");
+    let weights = Some(calc_weights_from_price(vec![target_price, target_price]));
+
+    test_utils.alice.create_market(2, Some(U128(0)));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    test_utils.bob.buy(market_id, buy_amount, 0, 0);
+    test_utils.bob.buy(market_id, buy_amount, 1, 0);
+
+    test_utils.carol.disable_market(market_id, "broken sources");
+    test_utils.carol.cancel_market(market_id, "broken sources");
+
+    // Purchases are refunded since the cancelled market is finalized
+    let bob_outcome_balance = test_utils.bob.get_outcome_balance(None, market_id, 0);
+    test_utils.bob.buy(market_id, buy_amount, 0, 0);
+    assert_eq!(test_utils.bob.get_outcome_balance(None, market_id, 0), bob_outcome_balance);
+    assert_eq!(test_utils.bob.get_token_balance(None), bob_init_balance - 2 * buy_amount);
+
+    // The market stays disabled but participants can still reclaim their basis
+    test_utils.alice.exit_liquidity(market_id, seed_amount);
+    test_utils.bob.claim_earnings(market_id);
+    test_utils.alice.claim_earnings(market_id);
+
+    assert_eq!(test_utils.alice.get_token_balance(None), alice_init_balance - creation_bond);
+    assert_eq!(test_utils.bob.get_token_balance(None), bob_init_balance);
+    assert_eq!(test_utils.bob.get_token_balance(Some(AMM_CONTRACT_ID.to_string())), 0);
+}
@@ -103,4 +103,101 @@ fn pricing_fee_test_calc() {
     let scaled_half = math::complex_mul_u128( to_yocto("1"), half, scale);
 
     assert_eq!(price, scaled_half);
-}
\ No newline at end of file
+}
+
+#[test]
+fn pricing_lmsr_initial_pricing_test() {
+    let test_utils = TestUtils::init(carol());
+    let market_id = 0;
+    let precision = 1_000_000;
+
+    let seed_amount = to_yocto("100");
+    let eighth = to_yocto("1") / 8;
+    let weights = Some(vec![U128(eighth); 8]);
+
+    test_utils.alice.create_market_with_pricing_model(8, Some(U128(0)), Some("Lmsr"));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    // With even weights no outcome tokens are returned to the LP
+    for outcome in 0..8 {
+        assert_eq!(test_utils.alice.get_outcome_balance(None, market_id, outcome), 0);
+        let price = test_utils.alice.get_spot_price_sans_fee(market_id, outcome);
+        assert!(std::cmp::max(price, eighth) - std::cmp::min(price, eighth) < precision);
+    }
+}
+
+#[test]
+fn pricing_lmsr_buy_sell_test() {
+    let test_utils = TestUtils::init(carol());
+    let market_id = 0;
+    let precision = 1_000_000;
+
+    let seed_amount = to_yocto("100");
+    let buy_amount = to_yocto("10");
+    let fourth = to_yocto("1") / 4;
+    let weights = Some(vec![U128(fourth); 4]);
+
+    test_utils.alice.create_market_with_pricing_model(4, Some(U128(0)), Some("Lmsr"));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    let expected_shares_out = test_utils.bob.calc_buy_amount(market_id, 1, buy_amount);
+    test_utils.bob.buy(market_id, buy_amount, 1, 0);
+    let shares_out = test_utils.bob.get_outcome_balance(None, market_id, 1);
+    assert_eq!(shares_out, expected_shares_out);
+    assert!(shares_out > buy_amount * 2);
+
+    let price_sum: u128 = (0..4).map(|outcome| test_utils.bob.get_spot_price_sans_fee(market_id, outcome)).sum();
+    assert!(std::cmp::max(price_sum, to_yocto("1")) - std::cmp::min(price_sum, to_yocto("1")) < precision);
+    assert!(test_utils.bob.get_spot_price_sans_fee(market_id, 1) > fourth);
+
+    // Selling back the bought shares returns about the collateral that was put in
+    let shares_in = test_utils.bob.calc_sell_amount(market_id, 1, buy_amount - precision);
+    assert!(shares_in <= shares_out);
+    test_utils.bob.sell(market_id, buy_amount - precision, 1, shares_out);
+    assert!(test_utils.bob.get_outcome_balance(None, market_id, 1) < precision * 10);
+}
+
+#[test]
+fn pricing_twap_follows_buy_and_sell_test() {
+    let test_utils = TestUtils::init(carol());
+
+    let market_id = 0;
+    let seed_amount = to_yocto("100");
+    let half = to_yocto("5") / 10;
+    let even_weights = Some(vec![U128(half), U128(half)]);
+    let hour = 3_600_000;
+
+    test_utils.alice.create_market(2, Some(U128(0)));
+    test_utils.alice.add_liquidity(market_id, seed_amount, even_weights);
+
+    // Without trades the average is the initial price
+    let time_a = test_utils.get_block_timestamp();
+    test_utils.set_block_timestamp(time_a + hour);
+    assert_eq!(test_utils.alice.get_twap(market_id, 0, hour), half);
+
+    test_utils.bob.buy(market_id, to_yocto("10"), 0, 0);
+    let bought_price = test_utils.alice.get_spot_price_sans_fee(market_id, 0);
+    assert!(bought_price > half);
+
+    // A window that starts after the trade only sees the new price
+    let time_b = test_utils.get_block_timestamp();
+    test_utils.set_block_timestamp(time_b + hour);
+    assert_eq!(test_utils.alice.get_twap(market_id, 0, hour / 2), bought_price);
+
+    let bob_shares = test_utils.bob.get_outcome_balance(None, market_id, 0);
+    test_utils.bob.sell_shares(market_id, bob_shares / 2, 0, 0);
+    let sold_price = test_utils.alice.get_spot_price_sans_fee(market_id, 0);
+    assert!(sold_price < bought_price);
+
+    // Each price is weighted by the time it was in effect, the trades land a few blocks after the time is set
+    let time_c = test_utils.get_block_timestamp();
+    test_utils.set_block_timestamp(time_c + hour);
+    let twap = test_utils.alice.get_twap(market_id, 0, 2 * hour);
+    let expected_twap = (bought_price + sold_price) / 2;
+    let tolerance = (bought_price - sold_price) / 100;
+    assert!(twap > expected_twap - tolerance && twap < expected_twap + tolerance, "twap {} expected {}", twap, expected_twap);
+
+    // `observe` returns the cumulatives the twap is derived from
+    let cumulatives = test_utils.alice.observe(market_id, 0, vec![2 * hour, 0]);
+    assert_eq!(cumulatives[1].wrapping_sub(cumulatives[0]) / u128::from(2 * hour), twap);
+}
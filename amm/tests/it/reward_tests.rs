@@ -0,0 +1,135 @@
+use crate::utils::*;
+use near_sdk::json_types::{U128};
+use near_sdk_sim::{to_yocto};
+
+fn get_reward_start_time(test_utils: &TestUtils, market_id: u64) -> u64 {
+    test_utils.alice.get_reward_program(market_id)["start_time"].as_str().unwrap().parse().unwrap()
+}
+
+#[test]
+fn reward_program_lp_rewards_test() {
+    let test_utils = TestUtils::init(carol());
+
+    // variables
+    let market_id = 0;
+    let seed_amount = to_yocto("100");
+    let half = U128(to_yocto("5") / 10);
+    let weights = Some(vec![half, half]);
+    let reward_amount = to_yocto("1");
+    let duration = 100_000_000; // ~1 day in ms
+    let reward_rate = reward_amount / duration as u128;
+    let elapsed = 3_600_000; // 1 hour in ms
+
+    test_utils.alice.create_market(2, Some(U128(0)));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    // Excess reward tokens are refunded to gov
+    let gov_init_balance = test_utils.carol.get_token_balance(None);
+    test_utils.carol.fund_reward_program(market_id, reward_amount * 2, reward_rate, duration);
+    assert_eq!(test_utils.carol.get_token_balance(None), gov_init_balance - reward_rate * duration as u128);
+
+    test_utils.bob.buy(market_id, to_yocto("1"), 0, 0);
+
+    // Alice is the only LP so she accrues everything that's emitted
+    let start_time = get_reward_start_time(&test_utils, market_id);
+    test_utils.set_block_timestamp(start_time + elapsed);
+    assert_eq!(test_utils.alice.get_claimable_rewards(market_id), reward_rate * elapsed as u128);
+
+    // Only LPs accrue rewards
+    assert_eq!(test_utils.bob.get_claimable_rewards(market_id), 0);
+
+    // Emissions stop at the end of the program
+    test_utils.set_block_timestamp(start_time + duration + elapsed);
+    let alice_init_balance = test_utils.alice.get_token_balance(None);
+    test_utils.alice.claim_rewards(market_id);
+    assert_eq!(test_utils.alice.get_token_balance(None), alice_init_balance + reward_rate * duration as u128);
+    assert_eq!(test_utils.alice.get_claimable_rewards(market_id), 0);
+}
+
+#[test]
+fn reward_program_pro_rata_test() {
+    let test_utils = TestUtils::init(carol());
+
+    // variables
+    let market_id = 0;
+    let precision = 10;
+    let seed_amount = to_yocto("100");
+    let half = U128(to_yocto("5") / 10);
+    let weights = Some(vec![half, half]);
+    let reward_amount = to_yocto("1");
+    let duration = 100_000_000; // ~1 day in ms
+    let reward_rate = reward_amount / duration as u128;
+    let elapsed = 3_600_000; // 1 hour in ms
+
+    test_utils.alice.create_market(2, Some(U128(0)));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+    test_utils.carol.fund_reward_program(market_id, reward_amount, reward_rate, duration);
+    let start_time = get_reward_start_time(&test_utils, market_id);
+
+    // Alice transfers half of her LP tokens to bob, rewards accrued before the transfer stay with alice
+    test_utils.set_block_timestamp(start_time + elapsed);
+    test_utils.alice.lp_transfer(market_id, test_utils.bob.account.account_id(), seed_amount / 2);
+
+    let time_a = test_utils.get_block_timestamp();
+    let alice_rewards_a = test_utils.alice.get_claimable_rewards(market_id);
+    let bob_rewards_a = test_utils.bob.get_claimable_rewards(market_id);
+    assert!(alice_rewards_a > bob_rewards_a);
+    assert_eq!(alice_rewards_a + bob_rewards_a, reward_rate * u128::from(time_a - start_time));
+
+    // Equal LP balances accrue equal rewards
+    test_utils.set_block_timestamp(time_a + elapsed);
+    let alice_rewards_b = test_utils.alice.get_claimable_rewards(market_id);
+    let bob_rewards_b = test_utils.bob.get_claimable_rewards(market_id);
+    assert_eq!(alice_rewards_b - alice_rewards_a, reward_rate * elapsed as u128 / 2);
+    assert_eq!(bob_rewards_b - bob_rewards_a, reward_rate * elapsed as u128 / 2);
+
+    // A new LP dilutes the existing LPs from the moment they join
+    test_utils.carol.add_liquidity(market_id, seed_amount, None);
+    let carol_pool_tokens = test_utils.carol.get_pool_token_balance(market_id, None);
+    let total_supply = seed_amount + carol_pool_tokens;
+
+    let time_c = test_utils.get_block_timestamp();
+    let alice_rewards_c = test_utils.alice.get_claimable_rewards(market_id);
+    let carol_rewards_c = test_utils.carol.get_claimable_rewards(market_id);
+
+    test_utils.set_block_timestamp(time_c + elapsed);
+    let alice_accrued = test_utils.alice.get_claimable_rewards(market_id) - alice_rewards_c;
+    let carol_accrued = test_utils.carol.get_claimable_rewards(market_id) - carol_rewards_c;
+    let emitted = reward_rate * elapsed as u128;
+    let expected_alice = emitted * (seed_amount / 2) / total_supply;
+    let expected_carol = emitted * carol_pool_tokens / total_supply;
+    assert!(std::cmp::max(alice_accrued, expected_alice) - std::cmp::min(alice_accrued, expected_alice) < precision);
+    assert!(std::cmp::max(carol_accrued, expected_carol) - std::cmp::min(carol_accrued, expected_carol) < precision);
+}
+
+#[test]
+fn reward_program_sweep_undistributed_test() {
+    let test_utils = TestUtils::init(carol());
+
+    // variables
+    let market_id = 0;
+    let seed_amount = to_yocto("100");
+    let half = U128(to_yocto("5") / 10);
+    let weights = Some(vec![half, half]);
+    let reward_amount = to_yocto("1");
+    let duration = 100_000_000; // ~1 day in ms
+    let reward_rate = reward_amount / duration as u128;
+
+    test_utils.alice.create_market(2, Some(U128(0)));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+    test_utils.carol.fund_reward_program(market_id, reward_amount, reward_rate, duration);
+    let start_time = get_reward_start_time(&test_utils, market_id);
+
+    // Nobody earns the rewards that are emitted after alice exits
+    test_utils.alice.exit_liquidity(market_id, seed_amount);
+    let alice_rewards = test_utils.alice.get_claimable_rewards(market_id);
+    assert!(alice_rewards > 0);
+
+    test_utils.set_block_timestamp(start_time + duration);
+    let gov_init_balance = test_utils.carol.get_token_balance(None);
+    test_utils.carol.sweep_undistributed_rewards(market_id);
+    let swept = test_utils.carol.get_token_balance(None) - gov_init_balance;
+
+    assert_eq!(swept + alice_rewards, reward_rate * duration as u128);
+    assert_eq!(test_utils.alice.get_claimable_rewards(market_id), alice_rewards);
+}
@@ -36,6 +36,116 @@ fn swap_calc_sell_collateral_out_test() {
     assert_eq!(buy_amount, 2111111111111111111111111);
 }
 
+#[test]
+fn swap_calc_buy_collateral_in_test() {
+    let test_utils = TestUtils::init(carol());
+
+    let market_id = 0;
+    let seed_amount = to_yocto("10");
+    let precision = 10;
+
+    let half = to_yocto("5") / 10;
+    let weights = Some(vec![U128(half), U128(half)]);
+
+    test_utils.alice.create_market(2, Some(U128(0)));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    let collateral_in = test_utils.alice.calc_buy_collateral_in(market_id, 0, 1909090909090909090909091);
+    assert!(collateral_in <= to_yocto("1"));
+    assert!(to_yocto("1") - collateral_in < precision);
+}
+
+#[test]
+fn swap_quote_buy_and_sell_test() {
+    let test_utils = TestUtils::init(carol());
+
+    let market_id = 0;
+    let seed_amount = to_yocto("10");
+    let collateral = to_yocto("1");
+
+    let half = to_yocto("5") / 10;
+    let weights = Some(vec![U128(half), U128(half)]);
+
+    test_utils.alice.create_market(2, Some(fee()));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    let as_u128 = |value: &near_sdk::serde_json::Value| value.as_str().unwrap().parse::<u128>().unwrap();
+
+    let buy_quote = test_utils.bob.quote_buy(market_id, 0, collateral);
+    assert_eq!(as_u128(&buy_quote["shares"]), test_utils.bob.calc_buy_amount(market_id, 0, collateral));
+    assert_eq!(as_u128(&buy_quote["spot_price_before"]), test_utils.bob.get_spot_price_sans_fee(market_id, 0));
+    assert!(as_u128(&buy_quote["price_impact"]) > 0);
+
+    // Quoting doesn't change the pool, the buy ends up at the quoted prices
+    test_utils.bob.buy(market_id, collateral, 0, as_u128(&buy_quote["shares"]));
+    for outcome in 0..2 {
+        assert_eq!(as_u128(&buy_quote["spot_prices_after"][outcome]), test_utils.bob.get_spot_price_sans_fee(market_id, outcome as u16));
+    }
+
+    let collateral_out = collateral / 2;
+    let sell_quote = test_utils.bob.quote_sell(market_id, 0, collateral_out);
+    assert_eq!(as_u128(&sell_quote["shares"]), test_utils.bob.calc_sell_amount(market_id, 0, collateral_out));
+
+    test_utils.bob.sell(market_id, collateral_out, 0, as_u128(&sell_quote["shares"]));
+    for outcome in 0..2 {
+        assert_eq!(as_u128(&sell_quote["spot_prices_after"][outcome]), test_utils.bob.get_spot_price_sans_fee(market_id, outcome as u16));
+    }
+}
+
+#[test]
+fn swap_buy_exact_shares_test() {
+    let test_utils = TestUtils::init(carol());
+
+    let market_id = 0;
+    let seed_amount = to_yocto("10");
+    let shares_out = to_yocto("2");
+
+    let half = to_yocto("5") / 10;
+    let weights = Some(vec![U128(half), U128(half)]);
+
+    test_utils.alice.create_market(2, Some(fee()));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    let collateral_in = test_utils.bob.calc_buy_collateral_in(market_id, 0, shares_out);
+    let init_balance_bob = test_utils.bob.get_token_balance(None);
+
+    // Transfer in more than needed, the remainder should be refunded
+    test_utils.bob.buy_exact_shares(market_id, to_yocto("5"), 0, shares_out, to_yocto("5"));
+
+    let balance_bob = test_utils.bob.get_token_balance(None);
+    assert_eq!(balance_bob, init_balance_bob - collateral_in);
+
+    let balance_amm = test_utils.bob.get_token_balance(Some(AMM_CONTRACT_ID.to_string()));
+    assert_eq!(balance_amm, seed_amount + collateral_in);
+
+    let target_buyer_balance = test_utils.bob.get_outcome_balance(None, market_id, 0);
+    assert!(target_buyer_balance >= shares_out);
+    assert!(target_buyer_balance - shares_out < 10);
+}
+
+#[test]
+fn swap_buy_exact_shares_max_collateral_test() {
+    let test_utils = TestUtils::init(carol());
+
+    let market_id = 0;
+    let seed_amount = to_yocto("10");
+
+    let half = to_yocto("5") / 10;
+    let weights = Some(vec![U128(half), U128(half)]);
+
+    test_utils.alice.create_market(2, Some(U128(0)));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    let init_balance_bob = test_utils.bob.get_token_balance(None);
+
+    // Buying ~1.9 shares costs ~1 token, capping at 0.5 token should fail and refund everything
+    test_utils.bob.buy_exact_shares(market_id, to_yocto("5"), 0, 1909090909090909090909091, to_yocto("5") / 10);
+
+    let balance_bob = test_utils.bob.get_token_balance(None);
+    assert_eq!(balance_bob, init_balance_bob);
+    assert_eq!(test_utils.bob.get_outcome_balance(None, market_id, 0), 0);
+}
+
 #[test]
 fn swap_basic_buy_test() {
     let test_utils = TestUtils::init(carol());
@@ -124,6 +234,76 @@ fn swap_basic_sell_test() {
     assert_eq!(amm_balance_post, seed_amount);
 }
 
+#[test]
+fn swap_sell_shares_test() {
+    let test_utils = TestUtils::init(carol());
+
+    let market_id = 0;
+    let seed_amount = to_yocto("10");
+    let buy_amount = to_yocto("1");
+    let precision = 10;
+    let half = to_yocto("5") / 10;
+    let weights = Some(vec![U128(half), U128(half)]);
+
+    test_utils.alice.create_market(2, Some(U128(0)));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    let init_balance_bob = test_utils.bob.get_token_balance(None);
+    test_utils.bob.buy(market_id, buy_amount, 0, 0);
+
+    let shares = test_utils.bob.get_outcome_balance(None, market_id, 0);
+    let collateral_out = test_utils.bob.calc_sell_shares_to_collateral(market_id, 0, shares);
+    assert!(buy_amount - collateral_out < precision);
+
+    // Sell the whole position
+    test_utils.bob.sell_shares(market_id, shares, 0, collateral_out);
+
+    let balance_bob = test_utils.bob.get_token_balance(None);
+    assert!(init_balance_bob - balance_bob < precision);
+    assert!(test_utils.bob.get_outcome_balance(None, market_id, 0) < precision);
+
+    let pool_balances = test_utils.alice.get_pool_balances(market_id);
+    assert!(seed_amount - pool_balances[1] < precision);
+}
+
+#[test]
+fn swap_outcomes_test() {
+    let test_utils = TestUtils::init(carol());
+
+    let market_id = 0;
+    let seed_amount = to_yocto("10");
+    let buy_amount = to_yocto("1");
+    let precision = 10;
+    let half = to_yocto("5") / 10;
+    let weights = Some(vec![U128(half), U128(half)]);
+
+    test_utils.alice.create_market(2, Some(fee()));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    test_utils.bob.buy(market_id, buy_amount, 0, 0);
+    let balance_bob = test_utils.bob.get_token_balance(None);
+
+    let shares = test_utils.bob.get_outcome_balance(None, market_id, 0);
+    test_utils.bob.swap_outcomes(market_id, 0, 1, shares, 1);
+
+    // No collateral left the contract
+    assert_eq!(test_utils.bob.get_token_balance(None), balance_bob);
+    assert_eq!(test_utils.bob.get_token_balance(Some(AMM_CONTRACT_ID.to_string())), seed_amount + buy_amount);
+
+    assert!(test_utils.bob.get_outcome_balance(None, market_id, 0) < precision);
+    assert!(test_utils.bob.get_outcome_balance(None, market_id, 1) > 0);
+
+    // Invalid resolution refunds bob's basis minus the fees paid
+    test_utils.alice.exit_liquidity(market_id, seed_amount);
+    test_utils.carol.resolute_market(market_id, None);
+    test_utils.bob.claim_earnings(market_id);
+    test_utils.alice.claim_earnings(market_id);
+
+    let bob_final_balance = test_utils.bob.get_token_balance(None);
+    assert!(bob_final_balance > balance_bob);
+    assert!(bob_final_balance <= balance_bob + buy_amount);
+}
+
 #[test]
 fn swap_complex_buy_test() {
     let test_utils = TestUtils::init(carol());
@@ -0,0 +1,341 @@
+use crate::utils::*;
+use near_sdk::json_types::{U128};
+use near_sdk_sim::{to_yocto};
+
+#[test]
+fn transfer_outcome_shares_test() {
+    let test_utils = TestUtils::init(carol());
+
+    let market_id = 0;
+    let seed_amount = to_yocto("10");
+    let buy_amount = to_yocto("1");
+    let half = to_yocto("5") / 10;
+    let weights = Some(vec![U128(half), U128(half)]);
+
+    test_utils.alice.create_market(2, Some(U128(0)));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    test_utils.bob.buy(market_id, buy_amount, 0, 0);
+
+    let shares = test_utils.bob.get_outcome_balance(None, market_id, 0);
+    test_utils.bob.outcome_ft_transfer(market_id, 0, carol(), shares / 2);
+
+    assert_eq!(test_utils.bob.get_outcome_balance(None, market_id, 0), shares - shares / 2);
+    assert_eq!(test_utils.carol.get_outcome_balance(None, market_id, 0), shares / 2);
+}
+
+#[test]
+fn transfer_outcome_shares_invalid_refund_test() {
+    let test_utils = TestUtils::init(carol());
+
+    let market_id = 0;
+    let seed_amount = to_yocto("10");
+    let buy_amount = to_yocto("1");
+    let precision = 10;
+    let half = to_yocto("5") / 10;
+    let weights = Some(vec![U128(half), U128(half)]);
+
+    test_utils.alice.create_market(2, Some(U128(0)));
+    let bob_init_balance = test_utils.bob.get_token_balance(None);
+    let carol_init_balance = test_utils.carol.get_token_balance(None);
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    test_utils.bob.buy(market_id, buy_amount, 0, 0);
+
+    let shares = test_utils.bob.get_outcome_balance(None, market_id, 0);
+    test_utils.bob.outcome_ft_transfer(market_id, 0, carol(), shares / 2);
+
+    test_utils.alice.exit_liquidity(market_id, seed_amount);
+    test_utils.carol.resolute_market(market_id, None);
+
+    test_utils.bob.claim_earnings(market_id);
+    test_utils.carol.claim_earnings(market_id);
+    test_utils.alice.claim_earnings(market_id);
+
+    // Bob's basis was split evenly between bob and carol
+    let bob_refund = test_utils.bob.get_token_balance(None) + buy_amount - bob_init_balance;
+    let carol_refund = test_utils.carol.get_token_balance(None) - carol_init_balance;
+    assert!(bob_refund + carol_refund <= buy_amount);
+    assert!(buy_amount - bob_refund - carol_refund < precision);
+    assert!(std::cmp::max(bob_refund, carol_refund) - std::cmp::min(bob_refund, carol_refund) < precision);
+}
+
+#[test]
+fn transfer_call_outcome_shares_refund_test() {
+    let test_utils = TestUtils::init(carol());
+    let receiver = test_utils.deploy_mock_receiver();
+
+    let market_id = 0;
+    let seed_amount = to_yocto("10");
+    let buy_amount = to_yocto("1");
+    let precision = 10;
+    let half = to_yocto("5") / 10;
+    let weights = Some(vec![U128(half), U128(half)]);
+
+    test_utils.alice.create_market(2, Some(U128(0)));
+    let bob_init_balance = test_utils.bob.get_token_balance(None);
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    test_utils.bob.buy(market_id, buy_amount, 0, 0);
+    let shares = test_utils.bob.get_outcome_balance(None, market_id, 0);
+
+    // The receiver uses a quarter of the shares and returns the rest
+    let unused = shares - shares / 4;
+    test_utils.bob.outcome_ft_transfer_call(market_id, 0, receiver.account_id(), shares, &unused.to_string());
+
+    assert_eq!(test_utils.bob.get_outcome_balance(None, market_id, 0), unused);
+    assert_eq!(test_utils.bob.get_outcome_balance(Some(receiver.account_id()), market_id, 0), shares / 4);
+
+    // Bob's spent on the returned shares moved back with them
+    test_utils.carol.resolute_market(market_id, None);
+    test_utils.bob.claim_earnings(market_id);
+
+    let bob_refund = test_utils.bob.get_token_balance(None) + buy_amount - bob_init_balance;
+    let expected_refund = buy_amount - buy_amount / 4;
+    assert!(std::cmp::max(bob_refund, expected_refund) - std::cmp::min(bob_refund, expected_refund) < precision);
+}
+
+#[test]
+fn transfer_call_outcome_shares_failed_receiver_test() {
+    let test_utils = TestUtils::init(carol());
+    let receiver = test_utils.deploy_mock_receiver();
+
+    let market_id = 0;
+    let seed_amount = to_yocto("10");
+    let buy_amount = to_yocto("1");
+    let half = to_yocto("5") / 10;
+    let weights = Some(vec![U128(half), U128(half)]);
+
+    test_utils.alice.create_market(2, Some(U128(0)));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    test_utils.bob.buy(market_id, buy_amount, 0, 0);
+    let shares = test_utils.bob.get_outcome_balance(None, market_id, 0);
+
+    // A receiver that panics gets all of its shares returned to the sender
+    test_utils.bob.outcome_ft_transfer_call(market_id, 0, receiver.account_id(), shares, "fail");
+
+    assert_eq!(test_utils.bob.get_outcome_balance(None, market_id, 0), shares);
+    assert_eq!(test_utils.bob.get_outcome_balance(Some(receiver.account_id()), market_id, 0), 0);
+}
+
+#[test]
+fn transfer_lp_tokens_test() {
+    let test_utils = TestUtils::init(carol());
+
+    let market_id = 0;
+    let seed_amount = to_yocto("10");
+    let buy_amount = to_yocto("1");
+    let half = to_yocto("5") / 10;
+    let weights = Some(vec![U128(half), U128(half)]);
+
+    test_utils.alice.create_market(2, Some(fee()));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    test_utils.carol.buy(market_id, buy_amount, 0, 0);
+    test_utils.carol.buy(market_id, buy_amount, 1, 0);
+
+    let alice_fees = test_utils.alice.get_fees_withdrawable(market_id, None);
+    assert!(alice_fees > 0);
+    let alice_balance = test_utils.alice.get_token_balance(None);
+
+    test_utils.alice.lp_transfer(market_id, test_utils.bob.account.account_id(), seed_amount / 2);
+
+    // Alice's accrued fees are paid out on transfer and bob doesn't inherit them
+    assert_eq!(test_utils.alice.get_token_balance(None), alice_balance + alice_fees);
+    assert_eq!(test_utils.alice.get_fees_withdrawable(market_id, None), 0);
+    assert_eq!(test_utils.bob.get_fees_withdrawable(market_id, None), 0);
+    assert_eq!(test_utils.alice.get_pool_token_balance(market_id, None), seed_amount / 2);
+    assert_eq!(test_utils.bob.get_pool_token_balance(market_id, None), seed_amount / 2);
+
+    // New fees are split evenly
+    test_utils.carol.buy(market_id, buy_amount, 0, 0);
+    let alice_new_fees = test_utils.alice.get_fees_withdrawable(market_id, None);
+    let bob_new_fees = test_utils.bob.get_fees_withdrawable(market_id, None);
+    assert!(alice_new_fees > 0);
+    assert!(std::cmp::max(alice_new_fees, bob_new_fees) - std::cmp::min(alice_new_fees, bob_new_fees) < 10);
+}
+
+#[test]
+fn transfer_lp_tokens_invalid_refund_test() {
+    let test_utils = TestUtils::init(carol());
+
+    let market_id = 0;
+    let seed_amount = to_yocto("10");
+    let half = to_yocto("5") / 10;
+    let weights = Some(vec![U128(half), U128(half)]);
+
+    test_utils.alice.create_market(2, Some(U128(0)));
+    let alice_init_balance = test_utils.alice.get_token_balance(None);
+    let bob_init_balance = test_utils.bob.get_token_balance(None);
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    test_utils.alice.lp_transfer(market_id, test_utils.bob.account.account_id(), seed_amount / 2);
+
+    test_utils.carol.resolute_market(market_id, None);
+    test_utils.alice.claim_earnings(market_id);
+    test_utils.bob.claim_earnings(market_id);
+
+    // Both LPs get refunded half of alice's initial liquidity
+    assert_eq!(test_utils.alice.get_token_balance(None), alice_init_balance - seed_amount / 2);
+    assert_eq!(test_utils.bob.get_token_balance(None), bob_init_balance + seed_amount / 2);
+}
+
+#[test]
+fn transfer_call_lp_tokens_refund_test() {
+    let test_utils = TestUtils::init(carol());
+    let receiver = test_utils.deploy_mock_receiver();
+
+    let market_id = 0;
+    let seed_amount = to_yocto("10");
+    let half = to_yocto("5") / 10;
+    let weights = Some(vec![U128(half), U128(half)]);
+
+    test_utils.alice.create_market(2, Some(U128(0)));
+    let alice_init_balance = test_utils.alice.get_token_balance(None);
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    // The receiver uses a quarter of the LP tokens and returns the rest
+    let unused = seed_amount - seed_amount / 4;
+    test_utils.alice.lp_transfer_call(market_id, receiver.account_id(), seed_amount, &unused.to_string());
+
+    assert_eq!(test_utils.alice.get_pool_token_balance(market_id, None), unused);
+    assert_eq!(test_utils.alice.get_pool_token_balance(market_id, Some(receiver.account_id())), seed_amount / 4);
+
+    // Alice's lp_spent on the returned LP tokens moved back with them
+    test_utils.carol.resolute_market(market_id, None);
+    test_utils.alice.claim_earnings(market_id);
+    assert_eq!(test_utils.alice.get_token_balance(None), alice_init_balance - seed_amount / 4);
+}
+
+#[test]
+fn transfer_lp_tokens_failed_fee_transfer_test() {
+    let test_utils = TestUtils::init(carol());
+
+    let market_id = 0;
+    let seed_amount = to_yocto("10");
+    let buy_amount = to_yocto("1");
+    let half = to_yocto("5") / 10;
+    let weights = Some(vec![U128(half), U128(half)]);
+
+    test_utils.alice.create_market(2, Some(fee()));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    // Dave isn't registered with the collateral token so every transfer to him fails
+    let dave = test_utils.master_account.account.create_user("dave".to_string(), init_balance());
+    storage_deposit(AMM_CONTRACT_ID, &test_utils.master_account.account, to_yocto("1"), Some(dave.account_id()));
+    let dave = TestAccount { account: dave };
+    test_utils.alice.lp_transfer(market_id, dave.account.account_id(), seed_amount / 2);
+
+    test_utils.carol.buy(market_id, buy_amount, 0, 0);
+    let dave_fees = dave.get_fees_withdrawable(market_id, None);
+    assert!(dave_fees > 0);
+
+    dave.lp_transfer(market_id, test_utils.bob.account.account_id(), seed_amount / 2);
+
+    // Dave's fees stay claimable after transferring all of his LP tokens and bob doesn't inherit them
+    assert_eq!(dave.get_pool_token_balance(market_id, None), 0);
+    assert_eq!(dave.get_fees_withdrawable(market_id, None), dave_fees);
+    assert_eq!(test_utils.bob.get_fees_withdrawable(market_id, None), 0);
+}
+
+#[test]
+fn transfer_multi_token_batch_test() {
+    let test_utils = TestUtils::init(carol());
+
+    let market_id = 0;
+    let seed_amount = to_yocto("10");
+    let buy_amount = to_yocto("1");
+    let half = to_yocto("5") / 10;
+    let weights = Some(vec![U128(half), U128(half)]);
+
+    test_utils.alice.create_market(2, Some(U128(0)));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+    test_utils.alice.buy(market_id, buy_amount, 0, 0);
+
+    let shares = test_utils.alice.mt_balance_of(None, "0:0");
+    assert_eq!(shares, test_utils.alice.get_outcome_balance(None, market_id, 0));
+    assert_eq!(test_utils.alice.mt_supply("0:lp"), Some(seed_amount));
+    assert_eq!(test_utils.alice.mt_supply("0:2"), None);
+
+    test_utils.alice.mt_batch_transfer(test_utils.bob.account.account_id(), vec!["0:0", "0:lp"], vec![shares, seed_amount / 2]);
+
+    assert_eq!(test_utils.bob.mt_balance_of(None, "0:0"), shares);
+    assert_eq!(test_utils.bob.mt_balance_of(None, "0:lp"), seed_amount / 2);
+    assert_eq!(test_utils.alice.mt_balance_of(None, "0:0"), 0);
+    assert_eq!(test_utils.alice.mt_balance_of(None, "0:lp"), seed_amount / 2);
+}
+
+#[test]
+fn transfer_call_multi_token_refund_test() {
+    let test_utils = TestUtils::init(carol());
+    let receiver = test_utils.deploy_mock_receiver();
+
+    let market_id = 0;
+    let seed_amount = to_yocto("10");
+    let buy_amount = to_yocto("1");
+    let half = to_yocto("5") / 10;
+    let weights = Some(vec![U128(half), U128(half)]);
+
+    test_utils.alice.create_market(2, Some(U128(0)));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+    test_utils.bob.buy(market_id, buy_amount, 0, 0);
+    let shares = test_utils.bob.mt_balance_of(None, "0:0");
+
+    // The receiver uses a quarter of the shares and returns the rest
+    let unused = shares - shares / 4;
+    test_utils.bob.mt_transfer_call(receiver.account_id(), "0:0", shares, &unused.to_string());
+
+    assert_eq!(test_utils.bob.mt_balance_of(None, "0:0"), unused);
+    assert_eq!(test_utils.bob.mt_balance_of(Some(receiver.account_id()), "0:0"), shares / 4);
+
+    // Unused amounts above the transferred amount are capped
+    test_utils.alice.mt_transfer_call(receiver.account_id(), "0:lp", seed_amount / 2, &seed_amount.to_string());
+
+    assert_eq!(test_utils.alice.mt_balance_of(None, "0:lp"), seed_amount);
+    assert_eq!(test_utils.alice.mt_balance_of(Some(receiver.account_id()), "0:lp"), 0);
+}
+
+#[test]
+fn transfer_call_multi_token_failed_receiver_test() {
+    let test_utils = TestUtils::init(carol());
+    let receiver = test_utils.deploy_mock_receiver();
+
+    let market_id = 0;
+    let seed_amount = to_yocto("10");
+    let buy_amount = to_yocto("1");
+    let half = to_yocto("5") / 10;
+    let weights = Some(vec![U128(half), U128(half)]);
+
+    test_utils.alice.create_market(2, Some(U128(0)));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+    test_utils.bob.buy(market_id, buy_amount, 0, 0);
+    let shares = test_utils.bob.mt_balance_of(None, "0:0");
+
+    // A receiver that panics gets all of its tokens returned to the sender
+    test_utils.bob.mt_transfer_call(receiver.account_id(), "0:0", shares, "fail");
+
+    assert_eq!(test_utils.bob.mt_balance_of(None, "0:0"), shares);
+    assert_eq!(test_utils.bob.mt_balance_of(Some(receiver.account_id()), "0:0"), 0);
+}
+
+#[test]
+fn transfer_requires_one_yocto_test() {
+    let test_utils = TestUtils::init(carol());
+
+    let market_id = 0;
+    let seed_amount = to_yocto("10");
+    let half = to_yocto("5") / 10;
+    let weights = Some(vec![U128(half), U128(half)]);
+
+    test_utils.alice.create_market(2, Some(U128(0)));
+    test_utils.alice.add_liquidity(market_id, seed_amount, weights);
+
+    // A function call access key can't attach a deposit, so transfers without exactly 1 yoctoNEAR are rejected
+    assert!(!test_utils.alice.mt_transfer(test_utils.bob.account.account_id(), "0:lp", seed_amount / 2, 0).is_ok());
+    assert!(!test_utils.alice.mt_transfer(test_utils.bob.account.account_id(), "0:lp", seed_amount / 2, 2).is_ok());
+    assert_eq!(test_utils.alice.mt_balance_of(None, "0:lp"), seed_amount);
+
+    assert!(test_utils.alice.mt_transfer(test_utils.bob.account.account_id(), "0:lp", seed_amount / 2, 1).is_ok());
+    assert_eq!(test_utils.alice.mt_balance_of(None, "0:lp"), seed_amount / 2);
+}
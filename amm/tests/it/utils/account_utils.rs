@@ -140,6 +140,45 @@ impl TestAccount {
         wrapped_balance.into()
     }
 
+    pub fn get_twap(&self, market_id: u64, outcome: u16, window: u64) -> u128 {
+        let wrapped_balance: U128 = self.account.view(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID,
+                "get_twap",
+                json!({
+                    "market_id": U64(market_id),
+                    "outcome": outcome,
+                    "window": U64(window)
+                }),
+                true
+            )
+        ).unwrap_json();
+
+        wrapped_balance.into()
+    }
+
+    // Returns the cumulative price of `outcome` at each of `ms_agos`
+    pub fn observe(&self, market_id: u64, outcome: u16, ms_agos: Vec<u64>) -> Vec<u128> {
+        let observations: near_sdk::serde_json::Value = self.account.view(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID,
+                "observe",
+                json!({
+                    "market_id": U64(market_id),
+                    "ms_agos": ms_agos.into_iter().map(U64).collect::<Vec<U64>>()
+                }),
+                true
+            )
+        ).unwrap_json();
+
+        observations
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|observation| observation["price_cumulatives"][outcome as usize].as_str().unwrap().parse().unwrap())
+            .collect()
+    }
+
     pub fn calc_buy_amount(&self, market_id: u64, outcome: u16, collateral_in: u128) -> u128 {
         let wrapped_balance: U128 = self.account.view(
             PendingContractTx::new(
@@ -157,6 +196,23 @@ impl TestAccount {
         wrapped_balance.into()
     }
 
+    pub fn calc_buy_collateral_in(&self, market_id: u64, outcome: u16, shares_out: u128) -> u128 {
+        let wrapped_balance: U128 = self.account.view(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "calc_buy_collateral_in", 
+                json!({
+                    "market_id": U64(market_id),
+                    "shares_out": U128(shares_out),
+                    "outcome_target": outcome
+                }), 
+                true
+            )
+        ).unwrap_json();
+
+        wrapped_balance.into()
+    }
+
     pub fn calc_sell_amount(&self, market_id: u64, outcome: u16, collateral_out: u128) -> u128 {
         let wrapped_balance: U128 = self.account.view(
             PendingContractTx::new(
@@ -174,6 +230,53 @@ impl TestAccount {
         wrapped_balance.into()
     }
 
+    pub fn quote_buy(&self, market_id: u64, outcome: u16, collateral_in: u128) -> near_sdk::serde_json::Value {
+        self.account.view(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "quote_buy", 
+                json!({
+                    "market_id": U64(market_id),
+                    "collateral_in": U128(collateral_in),
+                    "outcome_target": outcome
+                }), 
+                true
+            )
+        ).unwrap_json()
+    }
+
+    pub fn quote_sell(&self, market_id: u64, outcome: u16, collateral_out: u128) -> near_sdk::serde_json::Value {
+        self.account.view(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "quote_sell", 
+                json!({
+                    "market_id": U64(market_id),
+                    "collateral_out": U128(collateral_out),
+                    "outcome_target": outcome
+                }), 
+                true
+            )
+        ).unwrap_json()
+    }
+
+    pub fn calc_sell_shares_to_collateral(&self, market_id: u64, outcome: u16, shares_in: u128) -> u128 {
+        let wrapped_balance: U128 = self.account.view(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "calc_sell_shares_to_collateral", 
+                json!({
+                    "market_id": U64(market_id),
+                    "shares_in": U128(shares_in),
+                    "outcome_target": outcome
+                }), 
+                true
+            )
+        ).unwrap_json();
+
+        wrapped_balance.into()
+    }
+
     pub fn get_fees_withdrawable(&self, market_id: u64, account_id: Option<AccountId>) -> u128 {
         let account_id = match account_id {
             Some(account_id) => account_id,
@@ -195,8 +298,56 @@ impl TestAccount {
         wrapped_balance.into()
     }
 
+    pub fn mt_balance_of(&self, account_id: Option<AccountId>, token_id: &str) -> u128 {
+        let account_id = match account_id {
+            Some(account_id) => account_id,
+            None => self.account.account_id()
+        };
+
+        let wrapped_balance: U128 = self.account.view(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "mt_balance_of", 
+                json!({
+                    "account_id": account_id,
+                    "token_id": token_id
+                }), 
+                true
+            )
+        ).unwrap_json();
+
+        wrapped_balance.into()
+    }
+
+    pub fn mt_supply(&self, token_id: &str) -> Option<u128> {
+        let wrapped_supply: Option<U128> = self.account.view(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "mt_supply", 
+                json!({
+                    "token_id": token_id
+                }), 
+                true
+            )
+        ).unwrap_json();
+
+        wrapped_supply.map(|supply| supply.into())
+    }
+
     /*** Setters ***/
     pub fn create_market(&self, outcomes: u16, fee_opt: Option<U128>) -> ExecutionResult {
+        self.create_market_with_options(outcomes, fee_opt, None, None)
+    }
+
+    pub fn create_market_with_pricing_model(&self, outcomes: u16, fee_opt: Option<U128>, pricing_model: Option<&str>) -> ExecutionResult {
+        self.create_market_with_options(outcomes, fee_opt, pricing_model, None)
+    }
+
+    pub fn create_market_with_creator_fee(&self, outcomes: u16, fee_opt: Option<U128>, creator_fee: u16) -> ExecutionResult {
+        self.create_market_with_options(outcomes, fee_opt, None, Some(creator_fee))
+    }
+
+    fn create_market_with_options(&self, outcomes: u16, fee_opt: Option<U128>, pricing_model: Option<&str>, creator_fee: Option<u16>) -> ExecutionResult {
         let msg = json!({
             "CreateMarketArgs": {
                 "description": empty_string(),
@@ -213,7 +364,9 @@ impl TestAccount {
                 "resolution_time": env_time(),
                 "collateral_token_id": TOKEN_CONTRACT_ID,
                 "swap_fee": fee_opt,
-                "is_scalar": false
+                "is_scalar": false,
+                "pricing_model": pricing_model,
+                "creator_fee": creator_fee
             }
         }).to_string();
         self.ft_transfer_call(AMM_CONTRACT_ID.to_string(), to_yocto("100"), msg)
@@ -229,6 +382,16 @@ impl TestAccount {
         self.ft_transfer_call(AMM_CONTRACT_ID.to_string(), amount, msg)
     }
 
+    pub fn add_liquidity_zap(&self, market_id: u64, amount: u128, weights: Option<Vec<U128>>) -> ExecutionResult {
+        let msg  = json!({
+            "AddLiquidityZapArgs": {
+                "market_id": market_id.to_string(),
+                "weight_indication": weights,
+            }
+        }).to_string();
+        self.ft_transfer_call(AMM_CONTRACT_ID.to_string(), amount, msg)
+    }
+
     pub fn exit_liquidity(&self, market_id: u64, total_in: u128) -> ExecutionResult {
         let res = self.account.call(
             PendingContractTx::new(
@@ -248,6 +411,25 @@ impl TestAccount {
         res
     }
 
+    pub fn exit_pool_to_collateral(&self, market_id: u64, lp_in: u128, min_collateral_out: u128) -> ExecutionResult {
+        let res = self.account.call(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "exit_pool_to_collateral", 
+                json!({
+                    "market_id": U64(market_id),
+                    "lp_in": U128(lp_in),
+                    "min_collateral_out": U128(min_collateral_out)
+                }), 
+                true
+            ),
+            STORAGE_AMOUNT,
+            DEFAULT_GAS
+        );
+        assert!(res.is_ok(), "exit_pool_to_collateral failed with res: {:?}", res);
+        res
+    }
+
     pub fn buy(&self, market_id: u64, amount: u128, outcome: u16, min_amount_out: u128) -> ExecutionResult {
         let msg  = json!({
             "BuyArgs": {
@@ -258,6 +440,45 @@ impl TestAccount {
         }).to_string();
         self.ft_transfer_call(AMM_CONTRACT_ID.to_string(), amount, msg)
     }
+
+    pub fn buy_exact_shares(&self, market_id: u64, amount: u128, outcome: u16, shares_out: u128, max_collateral_in: u128) -> ExecutionResult {
+        let msg  = json!({
+            "BuyExactSharesArgs": {
+                "market_id": U64(market_id),
+                "outcome_target": outcome,
+                "shares_out": U128(shares_out),
+                "max_collateral_in": U128(max_collateral_in)
+            }
+        }).to_string();
+        self.ft_transfer_call(AMM_CONTRACT_ID.to_string(), amount, msg)
+    }
+
+    pub fn mint_complete_set(&self, market_id: u64, amount: u128) -> ExecutionResult {
+        let msg  = json!({
+            "MintCompleteSetArgs": {
+                "market_id": U64(market_id)
+            }
+        }).to_string();
+        self.ft_transfer_call(AMM_CONTRACT_ID.to_string(), amount, msg)
+    }
+
+    pub fn merge_complete_set(&self, market_id: u64, amount: u128) -> ExecutionResult {
+        let res = self.account.call(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "merge_complete_set", 
+                json!({
+                    "market_id": U64(market_id),
+                    "amount": U128(amount)
+                }), 
+                true
+            ),
+            STORAGE_AMOUNT,
+            DEFAULT_GAS
+        );
+        assert!(res.is_ok(), "merge_complete_set failed with res: {:?}", res);
+        res
+    }
     
     pub fn sell(&self, market_id: u64, amount_out: u128, outcome: u16, max_shares_in: u128) -> ExecutionResult {
         let res = self.account.call(
@@ -279,6 +500,193 @@ impl TestAccount {
         res
     }
 
+    pub fn sell_shares(&self, market_id: u64, shares_in: u128, outcome: u16, min_collateral_out: u128) -> ExecutionResult {
+        let res = self.account.call(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "sell_shares", 
+                json!({
+                    "market_id": U64(market_id),
+                    "outcome_target": outcome,
+                    "shares_in": U128(shares_in),
+                    "min_collateral_out": U128(min_collateral_out)
+                }), 
+                true
+            ),
+            STORAGE_AMOUNT,
+            DEFAULT_GAS
+        );
+        assert!(res.is_ok(), "sell_shares failed with res: {:?}", res);
+        res
+    }
+
+    pub fn swap_outcomes(&self, market_id: u64, outcome_in: u16, outcome_out: u16, shares_in: u128, min_shares_out: u128) -> ExecutionResult {
+        let res = self.account.call(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "swap_outcomes", 
+                json!({
+                    "market_id": U64(market_id),
+                    "outcome_in": outcome_in,
+                    "outcome_out": outcome_out,
+                    "shares_in": U128(shares_in),
+                    "min_shares_out": U128(min_shares_out)
+                }), 
+                true
+            ),
+            STORAGE_AMOUNT,
+            DEFAULT_GAS
+        );
+        assert!(res.is_ok(), "swap_outcomes failed with res: {:?}", res);
+        res
+    }
+
+    pub fn outcome_ft_transfer(&self, market_id: u64, outcome: u16, receiver_id: AccountId, amount: u128) -> ExecutionResult {
+        let res = self.account.call(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "outcome_ft_transfer", 
+                json!({
+                    "market_id": U64(market_id),
+                    "outcome": outcome,
+                    "receiver_id": receiver_id,
+                    "amount": U128(amount),
+                    "memo": None::<String>
+                }), 
+                true
+            ),
+            1,
+            DEFAULT_GAS
+        );
+        assert!(res.is_ok(), "outcome_ft_transfer failed with res: {:?}", res);
+        res
+    }
+
+    pub fn outcome_ft_transfer_call(&self, market_id: u64, outcome: u16, receiver_id: AccountId, amount: u128, msg: &str) -> ExecutionResult {
+        let res = self.account.call(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "outcome_ft_transfer_call", 
+                json!({
+                    "market_id": U64(market_id),
+                    "outcome": outcome,
+                    "receiver_id": receiver_id,
+                    "amount": U128(amount),
+                    "memo": None::<String>,
+                    "msg": msg
+                }), 
+                true
+            ),
+            1,
+            DEFAULT_GAS
+        );
+        assert!(res.is_ok(), "outcome_ft_transfer_call failed with res: {:?}", res);
+        res
+    }
+
+    pub fn lp_transfer(&self, market_id: u64, receiver_id: AccountId, amount: u128) -> ExecutionResult {
+        let res = self.account.call(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "lp_transfer", 
+                json!({
+                    "market_id": U64(market_id),
+                    "receiver_id": receiver_id,
+                    "amount": U128(amount)
+                }), 
+                true
+            ),
+            1,
+            DEFAULT_GAS
+        );
+        assert!(res.is_ok(), "lp_transfer failed with res: {:?}", res);
+        res
+    }
+
+    pub fn lp_transfer_call(&self, market_id: u64, receiver_id: AccountId, amount: u128, msg: &str) -> ExecutionResult {
+        let res = self.account.call(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "lp_transfer_call", 
+                json!({
+                    "market_id": U64(market_id),
+                    "receiver_id": receiver_id,
+                    "amount": U128(amount),
+                    "msg": msg
+                }), 
+                true
+            ),
+            1,
+            DEFAULT_GAS
+        );
+        assert!(res.is_ok(), "lp_transfer_call failed with res: {:?}", res);
+        res
+    }
+
+    // Doesn't assert the result so tests can check which deposits are rejected
+    pub fn mt_transfer(&self, receiver_id: AccountId, token_id: &str, amount: u128, deposit: u128) -> ExecutionResult {
+        self.account.call(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "mt_transfer", 
+                json!({
+                    "receiver_id": receiver_id,
+                    "token_id": token_id,
+                    "amount": U128(amount),
+                    "approval": None::<(AccountId, u64)>,
+                    "memo": None::<String>
+                }), 
+                true
+            ),
+            deposit,
+            DEFAULT_GAS
+        )
+    }
+
+    pub fn mt_batch_transfer(&self, receiver_id: AccountId, token_ids: Vec<&str>, amounts: Vec<u128>) -> ExecutionResult {
+        let amounts: Vec<U128> = amounts.into_iter().map(U128).collect();
+        let res = self.account.call(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "mt_batch_transfer", 
+                json!({
+                    "receiver_id": receiver_id,
+                    "token_ids": token_ids,
+                    "amounts": amounts,
+                    "approvals": None::<Vec<Option<(AccountId, u64)>>>,
+                    "memo": None::<String>
+                }), 
+                true
+            ),
+            1,
+            DEFAULT_GAS
+        );
+        assert!(res.is_ok(), "mt_batch_transfer failed with res: {:?}", res);
+        res
+    }
+
+    pub fn mt_transfer_call(&self, receiver_id: AccountId, token_id: &str, amount: u128, msg: &str) -> ExecutionResult {
+        let res = self.account.call(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "mt_transfer_call", 
+                json!({
+                    "receiver_id": receiver_id,
+                    "token_id": token_id,
+                    "amount": U128(amount),
+                    "approval": None::<(AccountId, u64)>,
+                    "memo": None::<String>,
+                    "msg": msg
+                }), 
+                true
+            ),
+            1,
+            DEFAULT_GAS
+        );
+        assert!(res.is_ok(), "mt_transfer_call failed with res: {:?}", res);
+        res
+    }
+
     pub fn redeem_collateral(&self, market_id: u64, amount_out: u128) -> ExecutionResult {
         let res = self.account.call(
             PendingContractTx::new(
@@ -315,6 +723,246 @@ impl TestAccount {
         res
     }
 
+    pub fn disable_market(&self, market_id: u64, reason: &str) -> ExecutionResult {
+        let res = self.account.call(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "disable_market", 
+                json!({
+                    "market_id": U64(market_id),
+                    "reason": reason,
+                }), 
+                true
+            ),
+            0,
+            DEFAULT_GAS
+        );
+        assert!(res.is_ok(), "disable_market failed with res: {:?}", res);
+        res
+    }
+
+    pub fn cancel_market(&self, market_id: u64, reason: &str) -> ExecutionResult {
+        let res = self.account.call(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "cancel_market", 
+                json!({
+                    "market_id": U64(market_id),
+                    "reason": reason,
+                }), 
+                true
+            ),
+            0,
+            DEFAULT_GAS
+        );
+        assert!(res.is_ok(), "cancel_market failed with res: {:?}", res);
+        res
+    }
+
+    pub fn set_protocol_fee_share(&self, protocol_fee_share: u16) -> ExecutionResult {
+        let res = self.account.call(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "set_protocol_fee_share", 
+                json!({
+                    "protocol_fee_share": protocol_fee_share,
+                }), 
+                true
+            ),
+            0,
+            DEFAULT_GAS
+        );
+        assert!(res.is_ok(), "set_protocol_fee_share failed with res: {:?}", res);
+        res
+    }
+
+    pub fn get_protocol_fees(&self, collateral_token_id: &str) -> u128 {
+        let wrapped_balance: U128 = self.account.view(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "get_protocol_fees", 
+                json!({
+                    "collateral_token_id": collateral_token_id,
+                }), 
+                true
+            )
+        ).unwrap_json();
+
+        wrapped_balance.into()
+    }
+
+    pub fn withdraw_protocol_fees(&self, collateral_token_id: &str) -> ExecutionResult {
+        let res = self.account.call(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "withdraw_protocol_fees", 
+                json!({
+                    "collateral_token_id": collateral_token_id,
+                }), 
+                true
+            ),
+            0,
+            DEFAULT_GAS
+        );
+        assert!(res.is_ok(), "withdraw_protocol_fees failed with res: {:?}", res);
+        res
+    }
+
+    pub fn get_creator_fees(&self, market_id: u64) -> u128 {
+        let wrapped_balance: U128 = self.account.view(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "get_creator_fees", 
+                json!({
+                    "market_id": U64(market_id),
+                }), 
+                true
+            )
+        ).unwrap_json();
+
+        wrapped_balance.into()
+    }
+
+    pub fn get_markets_by_creator(&self, creator: &str) -> Vec<U64> {
+        self.account.view(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "get_markets_by_creator", 
+                json!({
+                    "creator": creator,
+                    "from_index": 0,
+                    "limit": 100
+                }), 
+                true
+            )
+        ).unwrap_json()
+    }
+
+    pub fn claim_creator_fees(&self, market_id: u64) -> ExecutionResult {
+        let res = self.account.call(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "claim_creator_fees", 
+                json!({
+                    "market_id": U64(market_id),
+                }), 
+                true
+            ),
+            0,
+            DEFAULT_GAS
+        );
+        assert!(res.is_ok(), "claim_creator_fees failed with res: {:?}", res);
+        res
+    }
+
+    pub fn claim_lp_fees(&self, market_id: u64) -> ExecutionResult {
+        let res = self.account.call(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "claim_lp_fees", 
+                json!({
+                    "market_id": U64(market_id),
+                }), 
+                true
+            ),
+            STORAGE_AMOUNT,
+            DEFAULT_GAS
+        );
+        assert!(res.is_ok(), "claim_lp_fees failed with res: {:?}", res);
+        res
+    }
+
+    pub fn claim_lp_fees_batch(&self, market_ids: Vec<u64>) -> ExecutionResult {
+        let market_ids: Vec<U64> = market_ids.into_iter().map(U64).collect();
+        let res = self.account.call(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "claim_lp_fees_batch", 
+                json!({
+                    "market_ids": market_ids,
+                }), 
+                true
+            ),
+            STORAGE_AMOUNT,
+            DEFAULT_GAS
+        );
+        assert!(res.is_ok(), "claim_lp_fees_batch failed with res: {:?}", res);
+        res
+    }
+
+    pub fn fund_reward_program(&self, market_id: u64, amount: u128, reward_rate: u128, duration: u64) -> ExecutionResult {
+        let msg  = json!({
+            "FundRewardProgramArgs": {
+                "market_id": U64(market_id),
+                "reward_rate": U128(reward_rate),
+                "duration": U64(duration),
+            }
+        }).to_string();
+        self.ft_transfer_call(AMM_CONTRACT_ID.to_string(), amount, msg)
+    }
+
+    pub fn get_reward_program(&self, market_id: u64) -> near_sdk::serde_json::Value {
+        self.account.view(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID,
+                "get_reward_program",
+                json!({
+                    "market_id": U64(market_id)
+                }),
+                true
+            )
+        ).unwrap_json()
+    }
+
+    pub fn get_claimable_rewards(&self, market_id: u64) -> u128 {
+        let res: U128 = self.account.view(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID,
+                "get_claimable_rewards",
+                json!({
+                    "market_id": U64(market_id),
+                    "account_id": self.account.account_id()
+                }),
+                true
+            )
+        ).unwrap_json();
+        res.into()
+    }
+
+    pub fn claim_rewards(&self, market_id: u64) -> ExecutionResult {
+        let res = self.account.call(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "claim_rewards", 
+                json!({
+                    "market_id": U64(market_id),
+                }), 
+                true
+            ),
+            STORAGE_AMOUNT,
+            DEFAULT_GAS
+        );
+        assert!(res.is_ok(), "claim_rewards failed with res: {:?}", res);
+        res
+    }
+
+    pub fn sweep_undistributed_rewards(&self, market_id: u64) -> ExecutionResult {
+        let res = self.account.call(
+            PendingContractTx::new(
+                AMM_CONTRACT_ID, 
+                "sweep_undistributed_rewards", 
+                json!({
+                    "market_id": U64(market_id),
+                }), 
+                true
+            ),
+            0,
+            DEFAULT_GAS
+        );
+        assert!(res.is_ok(), "sweep_undistributed_rewards failed with res: {:?}", res);
+        res
+    }
+
     pub fn claim_earnings(&self, market_id: u64) -> ExecutionResult {
         let res = self.account.call(
             PendingContractTx::new(
@@ -44,6 +44,7 @@ type AMMContract = amm::AMMContractContract;
 pub const TOKEN_CONTRACT_ID: &str = "token";
 pub const AMM_CONTRACT_ID: &str = "amm";
 pub const ORACLE_CONTRACT_ID: &str = "oracle";
+pub const MOCK_RECEIVER_CONTRACT_ID: &str = "receiver";
 pub const SAFE_STORAGE_AMOUNT: u128 = 1250000000000000000000;
 
 // Load in contract bytes
@@ -51,6 +52,7 @@ near_sdk_sim::lazy_static! {
     static ref ORACLE_WASM_BYTES: &'static [u8] = include_bytes!("../../../../res/oracle.wasm").as_ref();
     static ref AMM_WASM_BYTES: &'static [u8] = include_bytes!("../../../../res/amm.wasm").as_ref();
     static ref TOKEN_WASM_BYTES: &'static [u8] = include_bytes!("../../../../res/token.wasm").as_ref();
+    static ref MOCK_RECEIVER_WASM_BYTES: &'static [u8] = include_bytes!("../../../../res/mock_receiver.wasm").as_ref();
 }
 
 pub struct TestUtils {
@@ -82,5 +84,23 @@ impl TestUtils {
             token_contract: token_init_res.contract, // should be doable like oracle and amm
         }
     }
+
+    // Returns the timestamp in ms of the block the next transaction or view is executed in
+    pub fn get_block_timestamp(&self) -> u64 {
+        self.master_account.account.borrow_runtime().cur_block.block_timestamp / 1_000_000
+    }
+
+    // Moves the chain forward to `timestamp` in ms, every block after it advances the time further
+    pub fn set_block_timestamp(&self, timestamp: u64) {
+        assert!(timestamp >= self.get_block_timestamp(), "can't move the chain back in time");
+        self.master_account.account.borrow_runtime_mut().cur_block.block_timestamp = timestamp * 1_000_000;
+    }
+
+    // Deploys a receiver for the AMM's transfer calls, its `msg` decides how many tokens it returns as unused
+    pub fn deploy_mock_receiver(&self) -> UserAccount {
+        let receiver = self.master_account.account.deploy(&MOCK_RECEIVER_WASM_BYTES, MOCK_RECEIVER_CONTRACT_ID.to_string(), to_yocto("100"));
+        storage_deposit(AMM_CONTRACT_ID, &self.master_account.account, SAFE_STORAGE_AMOUNT, Some(receiver.account_id()));
+        receiver
+    }
 }
 
@@ -9,4 +9,5 @@ fi
 
 cp ./target/wasm32-unknown-unknown/release/amm.wasm ./res
 cp ./target/wasm32-unknown-unknown/release/dao.wasm ./res
+cp ./target/wasm32-unknown-unknown/release/mock_receiver.wasm ./res
 cp ./target/wasm32-unknown-unknown/release/token.wasm ./res
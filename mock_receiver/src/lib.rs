@@ -0,0 +1,74 @@
+/**
+* Token receiver used by the AMM integration tests
+*
+* Implements the receiver side of the AMM's `*_transfer_call` methods.
+* The `msg` passed along with a transfer decides what the receiver does:
+* "fail" makes the call panic, a number is returned as the amount of unused tokens and anything else uses every token.
+*/
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::{U64, U128};
+use near_sdk::{env, near_bindgen, AccountId};
+
+#[global_allocator]
+static ALLOC: near_sdk::wee_alloc::WeeAlloc<'_> = near_sdk::wee_alloc::WeeAlloc::INIT;
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+pub struct MockReceiver {}
+
+#[near_bindgen]
+#[allow(unused_variables)]
+impl MockReceiver {
+    /**
+     * @returns the amount of unused outcome tokens as instructed by `msg`
+     */
+    pub fn outcome_ft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        market_id: U64,
+        outcome: u16,
+        amount: U128,
+        msg: String
+    ) -> U128 {
+        U128(unused_amount(&msg))
+    }
+
+    /**
+     * @returns the amount of unused LP tokens as instructed by `msg`
+     */
+    pub fn lp_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        market_id: U64,
+        amount: U128,
+        msg: String
+    ) -> U128 {
+        U128(unused_amount(&msg))
+    }
+
+    /**
+     * @returns the amount of unused tokens for every token id as instructed by `msg`
+     */
+    pub fn mt_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        previous_owner_ids: Vec<AccountId>,
+        token_ids: Vec<String>,
+        amounts: Vec<U128>,
+        msg: String
+    ) -> Vec<U128> {
+        let unused = unused_amount(&msg);
+        amounts.iter().map(|_| U128(unused)).collect()
+    }
+}
+
+/**
+ * @param msg is the message that was passed along with the transfer
+ * @returns the amount of tokens the receiver should return as unused
+ */
+fn unused_amount(msg: &str) -> u128 {
+    if msg == "fail" {
+        env::panic(b"ERR_RECEIVER_FAILED");
+    }
+    msg.parse().unwrap_or(0)
+}